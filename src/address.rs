@@ -0,0 +1,258 @@
+use std::fmt;
+
+use crate::error::TransactionError;
+
+/// Validates a Solana wallet address format
+/// it uses the Solana address format
+///
+/// A Solana address is a base58-encoded 32-byte public key, so the address must
+/// decode cleanly and produce exactly 32 bytes.
+///
+/// Most keys encode to 43 or 44 characters, but each leading zero byte encodes as a
+/// single `1`, so keys with leading zeros are shorter; the System Program's address,
+/// 32 bytes of zeros, is 32 `1`s. Lengths from 32 to 44 are therefore accepted, and the
+/// decoded length, not the encoded one, rules out short strings that are not keys.
+///
+/// # Arguments
+///
+/// * `address` - The address to validate
+///
+/// # Returns
+///
+/// * `bool` - True if the address is valid, false otherwise
+/// 
+pub fn is_valid_solana_address(address: &str) -> bool {
+    // Fast path: a base58-encoded 32-byte key is between 32 (all zeros) and 44 characters long
+    if !(32..=44).contains(&address.len()) {
+        return false;
+    }
+
+    // Decode into a 32-byte buffer; longer keys fail and shorter ones report fewer bytes
+    let mut key = [0u8; 32];
+    matches!(bs58::decode(address).onto(&mut key), Ok(32))
+}
+
+/// Checks whether a string is a well-formed address on some chain
+///
+/// Implementations let the balance calculations run over histories from chains other
+/// than Solana, via [`calculate_wallet_balance_with_validator`](crate::calculate_wallet_balance_with_validator).
+pub trait AddressValidator {
+    /// Returns true if `address` is well-formed
+    fn validate(&self, address: &str) -> bool;
+}
+
+/// Accepts base58-encoded 32-byte Solana public keys, as [`is_valid_solana_address`] does
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SolanaValidator;
+
+impl AddressValidator for SolanaValidator {
+    fn validate(&self, address: &str) -> bool {
+        is_valid_solana_address(address)
+    }
+}
+
+/// Accepts Ethereum addresses: `0x` followed by 40 hexadecimal digits, in any case
+///
+/// The mixed-case checksum is not verified.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EthereumValidator;
+
+impl AddressValidator for EthereumValidator {
+    fn validate(&self, address: &str) -> bool {
+        address
+            .strip_prefix("0x")
+            .is_some_and(|hex| hex.len() == 40 && hex.bytes().all(|b| b.is_ascii_hexdigit()))
+    }
+}
+
+/// Normalizes a wallet address taken from an external source
+///
+/// Surrounding whitespace is trimmed. Base58 is case-sensitive, so an address whose case
+/// has been mangled (for instance by a data source that lowercases strings) cannot be
+/// repaired and is rejected rather than silently "fixed".
+///
+/// # Arguments
+///
+/// * `address` - The raw address to normalize
+///
+/// # Returns
+///
+/// * `Ok(String)` - The trimmed, validated address
+/// * `Err(TransactionError)` - If the trimmed address is empty or not a valid Solana address
+///
+pub fn normalize_wallet_address(address: &str) -> Result<String, TransactionError> {
+    let trimmed = address.trim();
+    validate_address(trimmed)?;
+    Ok(trimmed.to_string())
+}
+
+/// A Solana wallet address that is known to be valid
+///
+/// The only way to obtain one is [`WalletAddress::parse`], so a function taking a
+/// `&WalletAddress` cannot be handed an unvalidated string.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct WalletAddress(String);
+
+impl WalletAddress {
+    /// Parses and validates a wallet address, trimming surrounding whitespace
+    ///
+    /// # Arguments
+    ///
+    /// * `address` - The raw address to parse
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(WalletAddress)` - The trimmed, validated address
+    /// * `Err(TransactionError)` - If the address is empty or not a valid Solana address
+    ///
+    pub fn parse(address: &str) -> Result<WalletAddress, TransactionError> {
+        normalize_wallet_address(address).map(WalletAddress)
+    }
+
+    /// Returns the address as a string slice
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for WalletAddress {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for WalletAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Validates a wallet address, returning an error describing why it was rejected
+pub(crate) fn validate_address(address: &str) -> Result<(), TransactionError> {
+    if address.is_empty() {
+        return Err(TransactionError::InvalidWalletAddress("Empty address".to_string()));
+    }
+    if !is_valid_solana_address(address) {
+        return Err(TransactionError::InvalidWalletAddress(address.to_string()));
+    }
+    Ok(())
+}
+
+/// Validates an SPL token mint address, which shares the Solana address format
+pub(crate) fn validate_mint(mint: &str) -> Result<(), TransactionError> {
+    if !is_valid_solana_address(mint) {
+        return Err(TransactionError::InvalidMintAddress(mint.to_string()));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests validating a large batch of addresses
+    #[test]
+    fn test_validate_many_addresses() {
+        let valid = (0..10_000)
+            .filter(|_| is_valid_solana_address("ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3"))
+            .count();
+        assert_eq!(valid, 10_000);
+    }
+
+    /// Tests that an address decoding to a 32-byte key is accepted
+    #[test]
+    fn test_valid_32_byte_key() {
+        assert!(is_valid_solana_address("9jLkNAaW9E47LQMHvjohy2uAAyr1331bAxgJKFRU7wF6"));
+    }
+
+    /// Tests that a well-shaped address decoding to only 31 bytes is rejected
+    #[test]
+    fn test_reject_31_byte_key() {
+        assert!(!is_valid_solana_address("4oprGx9GJLatypMHd8N9TQy6pLQ3MqVmM4JGSu1fWaz"));
+    }
+
+    /// Tests accept and reject decisions at the boundary lengths of an encoded key
+    #[test]
+    fn test_address_length_boundaries() {
+        let cases = [
+            // 31 characters is too short for any 32-byte key
+            ("1111111111111111111111111111111", false),
+            // 32 characters: the all-zero System Program key, and a string decoding to fewer bytes
+            ("11111111111111111111111111111111", true),
+            ("22222222222222222222222222222222", false),
+            // 43 and 44 characters: typical keys, including the largest possible one
+            ("4vJ9JU1bJJE96FWSJKvHsmmFADCg4gpZQff4P3bkLKi", true),
+            ("ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3", true),
+            ("JEKNVnkbo3jma5nREBBJCDoXFVeKkD56V3xKrvRmWxFG", true),
+            // 45 characters is too long for any 32-byte key, here one with an extra leading zero byte
+            ("1ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3", false),
+        ];
+        for (address, valid) in cases {
+            assert_eq!(is_valid_solana_address(address), valid, "{} ({} chars)", address, address.len());
+        }
+    }
+
+    /// Tests that an address containing a non-base58 character is rejected
+    #[test]
+    fn test_reject_invalid_base58_char() {
+        assert!(!is_valid_solana_address("0LiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3"));
+    }
+
+    /// Tests that the Solana validator matches the built-in address check
+    #[test]
+    fn test_solana_validator() {
+        assert!(SolanaValidator.validate("ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3"));
+        assert!(!SolanaValidator.validate("4oprGx9GJLatypMHd8N9TQy6pLQ3MqVmM4JGSu1fWaz"));
+        assert!(!SolanaValidator.validate("0x52908400098527886E0F7030069857D2E4169EE7"));
+    }
+
+    /// Tests that the Ethereum validator requires `0x` and exactly 40 hex digits
+    #[test]
+    fn test_ethereum_validator() {
+        assert!(EthereumValidator.validate("0x52908400098527886E0F7030069857D2E4169EE7"));
+        assert!(EthereumValidator.validate("0xde709f2102306220921060314715629080e2fb77"));
+        assert!(!EthereumValidator.validate("52908400098527886E0F7030069857D2E4169EE7"));
+        assert!(!EthereumValidator.validate("0x52908400098527886E0F7030069857D2E4169EE"));
+        assert!(!EthereumValidator.validate("0x52908400098527886E0F7030069857D2E4169EEG"));
+        assert!(!EthereumValidator.validate("ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3"));
+    }
+
+    /// Tests that surrounding whitespace is trimmed
+    #[test]
+    fn test_normalize_trims_whitespace() {
+        assert_eq!(
+            normalize_wallet_address("  ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3\n").unwrap(),
+            "ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3"
+        );
+    }
+
+    /// Tests that a lowercased address is rejected rather than repaired
+    #[test]
+    fn test_normalize_rejects_case_mangled_address() {
+        assert!(matches!(
+            normalize_wallet_address("aliceqzuf4vyuxtu1uqvzdqbpgyyfrxh6kqxwfb8nqp3"),
+            Err(TransactionError::InvalidWalletAddress(_))
+        ));
+    }
+
+    /// Tests parsing a valid address, trimming surrounding whitespace
+    #[test]
+    fn test_wallet_address_parse() {
+        let address = WalletAddress::parse(" ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3\n").unwrap();
+        assert_eq!(address.as_str(), "ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3");
+        assert_eq!(address.to_string(), "ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3");
+    }
+
+    /// Tests that an invalid string cannot become a wallet address
+    #[test]
+    fn test_wallet_address_parse_invalid() {
+        assert!(matches!(
+            WalletAddress::parse("not-a-wallet"),
+            Err(TransactionError::InvalidWalletAddress(address)) if address == "not-a-wallet"
+        ));
+        assert!(matches!(
+            WalletAddress::parse("   "),
+            Err(TransactionError::InvalidWalletAddress(_))
+        ));
+    }
+}
@@ -0,0 +1,559 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use crate::balance::{apply_transaction, balances_sorted, calculate_native_balances, locate_error, transactions_for_wallet};
+use crate::error::TransactionError;
+use crate::transaction::{validate_amount, Transaction, TransactionStatus, TransactionType};
+
+/// Aggregate money movement across a batch of transactions
+#[derive(Debug, Clone, PartialEq)]
+pub struct NetFlow {
+    /// The sum of all deposits
+    pub total_in: i64,
+    /// The sum of all withdrawals
+    pub total_out: i64,
+    /// Deposits minus withdrawals
+    pub net: i64,
+}
+
+/// A wallet whose native SOL balance crossed a monitoring threshold
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ThresholdAlert {
+    /// The address of the wallet
+    pub wallet: String,
+    /// The wallet's final native SOL balance
+    pub balance: i64,
+    /// Whether the final balance is at or above the threshold, rather than below it
+    pub above: bool,
+}
+
+/// Counts transactions of each type across all wallets
+///
+/// Because a transfer's destination is part of its type, transfers to different
+/// destinations are counted under separate keys.
+///
+/// # Arguments
+///
+/// * `transactions` - A slice of transactions to count
+///
+/// # Returns
+///
+/// * `HashMap<TransactionType, usize>` - The number of transactions of each type present
+///
+pub fn count_by_type(transactions: &[Transaction]) -> HashMap<TransactionType, usize> {
+    let mut counts = HashMap::new();
+    for tx in transactions {
+        *counts.entry(tx.transaction_type.clone()).or_insert(0) += 1;
+    }
+    counts
+}
+
+/// Sums deposits and withdrawals across every wallet in the batch
+///
+/// Staking rewards enter the batch and count as inflows; standalone fees leave it and
+/// count as outflows. Transfers move funds between
+/// wallets inside the batch, so they are neither inflows nor outflows, and opening balances
+/// only seed a wallet's starting point. Failed transactions are skipped.
+///
+/// # Arguments
+///
+/// * `transactions` - A slice of transactions to aggregate
+///
+/// # Returns
+///
+/// * `Ok(NetFlow)` - The total inflow, outflow, and net flow
+/// * `Err(TransactionError)` - If an amount is invalid or a total overflows
+///
+pub fn net_flow(transactions: &[Transaction]) -> Result<NetFlow, TransactionError> {
+    let mut total_in = 0i64;
+    let mut total_out = 0i64;
+    for (index, tx) in transactions.iter().enumerate() {
+        validate_amount(tx.amount).map_err(|e| locate_error(index, e))?;
+        if tx.status == TransactionStatus::Failed {
+            continue;
+        }
+
+        let overflow = || TransactionError::BalanceOverflow(tx.wallet_address.clone());
+        match tx.transaction_type {
+            TransactionType::Deposit | TransactionType::StakingReward => total_in = total_in.checked_add(tx.amount).ok_or_else(overflow)?,
+            TransactionType::Withdrawal | TransactionType::Fee => total_out = total_out.checked_add(tx.amount).ok_or_else(overflow)?,
+            TransactionType::Transfer { .. } | TransactionType::OpeningBalance => {}
+        }
+    }
+
+    // Both totals are non-negative, so their difference always fits in an i64
+    Ok(NetFlow {
+        total_in,
+        total_out,
+        net: total_in - total_out,
+    })
+}
+
+/// Finds the wallets with the largest native SOL balances
+///
+/// Wallets are sorted by balance, highest first, with ties broken by address so the
+/// result is deterministic.
+///
+/// # Arguments
+///
+/// * `transactions` - A slice of transactions to process
+/// * `n` - The number of wallets to return; all wallets are returned if there are fewer
+///
+/// # Returns
+///
+/// * `Ok(Vec<(String, i64)>)` - Up to `n` `(address, balance)` pairs, richest first
+/// * `Err(TransactionError)` - If there's an error processing the transactions
+///
+pub fn top_wallets_by_balance(transactions: &[Transaction], n: usize) -> Result<Vec<(String, i64)>, TransactionError> {
+    let mut balances: Vec<(String, i64)> = calculate_native_balances(transactions)?.into_iter().collect();
+    balances.sort_by(|(a_wallet, a_balance), (b_wallet, b_balance)| {
+        b_balance.cmp(a_balance).then_with(|| a_wallet.cmp(b_wallet))
+    });
+    balances.truncate(n);
+    Ok(balances)
+}
+
+/// Finds the wallets whose native SOL balance is overdrawn
+///
+/// # Arguments
+///
+/// * `transactions` - A slice of transactions to process
+///
+/// # Returns
+///
+/// * `Ok(HashMap<String, i64>)` - The balance of each wallet strictly below zero, keyed by address
+/// * `Err(TransactionError)` - If there's an error processing the transactions
+///
+pub fn negative_balance_wallets(transactions: &[Transaction]) -> Result<HashMap<String, i64>, TransactionError> {
+    let mut balances = calculate_native_balances(transactions)?;
+    balances.retain(|_, balance| *balance < 0);
+    Ok(balances)
+}
+
+/// Reports the wallets whose native SOL balance crosses a threshold, and which side they end on
+///
+/// Each wallet's running balance is replayed from zero in slice order, and a wallet is
+/// reported if any transaction moves it from one side of the threshold to the other, even
+/// if a later one moves it back. Wallets that never cross, such as one that stays below the
+/// threshold throughout, are left out. A balance exactly at the threshold counts as above
+/// it, so a wallet must fall strictly short of the threshold to be below.
+///
+/// # Arguments
+///
+/// * `transactions` - A slice of transactions to process
+/// * `threshold` - The balance to compare each wallet against
+///
+/// # Returns
+///
+/// * `Ok(Vec<ThresholdAlert>)` - One alert per crossing wallet with its final balance, sorted by address
+/// * `Err(TransactionError)` - If there's an error processing the transactions
+///
+pub fn threshold_alerts(transactions: &[Transaction], threshold: i64) -> Result<Vec<ThresholdAlert>, TransactionError> {
+    let balances = balances_sorted(transactions)?;
+
+    // Replay each wallet's running balance, noting the wallets that change sides
+    let mut running: HashMap<&str, i64> = HashMap::new();
+    let mut crossed = HashSet::new();
+    for tx in transactions.iter().filter(|tx| tx.mint.is_none()) {
+        let mut involved = vec![tx.wallet_address.as_str()];
+        if let TransactionType::Transfer { to } = &tx.transaction_type {
+            involved.push(to.as_str());
+        }
+        for wallet in involved {
+            let balance = running.entry(wallet).or_insert(0);
+            let was_above = *balance >= threshold;
+            *balance = apply_transaction(*balance, tx, wallet)?;
+            if was_above != (*balance >= threshold) {
+                crossed.insert(wallet);
+            }
+        }
+    }
+
+    Ok(balances
+        .into_iter()
+        .filter(|(wallet, _)| crossed.contains(wallet.as_str()))
+        .map(|(wallet, balance)| ThresholdAlert {
+            wallet,
+            balance,
+            above: balance >= threshold,
+        })
+        .collect())
+}
+
+/// Finds a wallet's biggest single movement of native SOL
+///
+/// Both directions count, so a large withdrawal or outgoing transfer can outrank every
+/// deposit. SPL token transactions, whose amounts are in other units, and failed
+/// transactions are skipped. Of several equally large transactions, the first is returned.
+///
+/// # Arguments
+///
+/// * `wallet` - The address of the wallet to search
+/// * `transactions` - A slice of transactions to search
+///
+/// # Returns
+///
+/// * `Option<Transaction>` - The transaction with the greatest absolute amount, or `None` if
+///   no transaction involves the wallet
+///
+pub fn largest_transaction(wallet: &str, transactions: &[Transaction]) -> Option<Transaction> {
+    // A strict comparison keeps the earlier of two equal amounts, unlike max_by_key
+    transactions_for_wallet(wallet, transactions)
+        .filter(|tx| tx.mint.is_none() && tx.status != TransactionStatus::Failed)
+        .reduce(|best, tx| if tx.amount.unsigned_abs() > best.amount.unsigned_abs() { tx } else { best })
+        .cloned()
+}
+
+/// Totals withdrawals by spending category, inferred from each transaction's memo
+///
+/// Each withdrawal is assigned to the first rule whose keyword appears in its memo,
+/// ignoring case; withdrawals without a matching memo go to `"uncategorized"`. Other
+/// transaction types and failed withdrawals are skipped. Totals saturate at `i64::MAX`.
+///
+/// # Arguments
+///
+/// * `transactions` - A slice of transactions to categorize
+/// * `rules` - `(keyword, category)` pairs, checked in order
+///
+/// # Returns
+///
+/// * `HashMap<String, i64>` - The total withdrawn in each category that has any withdrawals
+///
+pub fn categorize(transactions: &[Transaction], rules: &[(String, String)]) -> HashMap<String, i64> {
+    let rules: Vec<(String, &str)> = rules
+        .iter()
+        .map(|(keyword, category)| (keyword.to_lowercase(), category.as_str()))
+        .collect();
+
+    let mut totals = HashMap::new();
+    for tx in transactions {
+        if tx.transaction_type != TransactionType::Withdrawal || tx.status == TransactionStatus::Failed {
+            continue;
+        }
+        let memo = tx.memo.as_deref().unwrap_or_default().to_lowercase();
+        let category = rules
+            .iter()
+            .find(|(keyword, _)| memo.contains(keyword.as_str()))
+            .map_or("uncategorized", |(_, category)| category);
+
+        let total = totals.entry(category.to_string()).or_insert(0i64);
+        *total = total.saturating_add(tx.amount);
+    }
+    totals
+}
+
+/// Lists the wallets that have transacted at or after a point in time
+///
+/// Both the source and the destination of a transfer count as active.
+///
+/// # Arguments
+///
+/// * `transactions` - A slice of transactions to scan
+/// * `since` - The start of the window, in Unix seconds (inclusive)
+///
+/// # Returns
+///
+/// * `HashSet<String>` - The addresses with at least one transaction at or after `since`
+///
+pub fn active_wallets_since(transactions: &[Transaction], since: i64) -> HashSet<String> {
+    let mut active = HashSet::new();
+    for tx in transactions.iter().filter(|tx| tx.timestamp >= since) {
+        active.insert(tx.wallet_address.clone());
+        if let TransactionType::Transfer { to } = &tx.transaction_type {
+            active.insert(to.clone());
+        }
+    }
+    active
+}
+
+/// Nets the native SOL transfers between each pair of wallets into a single signed amount
+///
+/// Each pair is keyed with its addresses in sorted order, and the value is the net amount
+/// the first wallet sent the second; a negative value means the second wallet sent more.
+/// Pairs whose transfers cancel out map to zero. Other transaction types, SPL token
+/// transfers, and failed transfers are skipped. Amounts saturate at the bounds of `i64`.
+///
+/// # Arguments
+///
+/// * `transactions` - A slice of transactions to settle
+///
+/// # Returns
+///
+/// * `HashMap<(String, String), i64>` - The net flow between every pair of wallets that transferred funds
+///
+pub fn net_settlements(transactions: &[Transaction]) -> HashMap<(String, String), i64> {
+    let mut settlements = HashMap::new();
+    for tx in transactions {
+        let TransactionType::Transfer { to } = &tx.transaction_type else {
+            continue;
+        };
+        if tx.mint.is_some() || tx.status == TransactionStatus::Failed {
+            continue;
+        }
+
+        let (key, amount) = if tx.wallet_address <= *to {
+            ((tx.wallet_address.clone(), to.clone()), tx.amount)
+        } else {
+            ((to.clone(), tx.wallet_address.clone()), tx.amount.saturating_neg())
+        };
+        let net = settlements.entry(key).or_insert(0i64);
+        *net = net.saturating_add(amount);
+    }
+    settlements
+}
+
+/// Counts transactions by size, grouping amounts into fixed-width buckets
+///
+/// Each bucket covers `[lower, lower + bucket_size)`. Transactions of every type, wallet,
+/// and mint are counted.
+///
+/// # Arguments
+///
+/// * `transactions` - A slice of transactions to count
+/// * `bucket_size` - The width of each bucket (must be positive)
+///
+/// # Returns
+///
+/// * `Ok(BTreeMap<i64, usize>)` - The number of transactions in each non-empty bucket, keyed by its lower bound
+/// * `Err(TransactionError)` - `InvalidBucketSize` if `bucket_size` is zero or negative, or
+///   `InvalidTransaction` if an amount is zero or negative
+///
+pub fn amount_histogram(transactions: &[Transaction], bucket_size: i64) -> Result<BTreeMap<i64, usize>, TransactionError> {
+    if bucket_size <= 0 {
+        return Err(TransactionError::InvalidBucketSize(bucket_size));
+    }
+    let mut histogram = BTreeMap::new();
+    for (index, tx) in transactions.iter().enumerate() {
+        // A positive amount rounds down to a bucket between zero and itself, so this cannot overflow
+        validate_amount(tx.amount).map_err(|e| locate_error(index, e))?;
+        let lower = tx.amount.div_euclid(bucket_size) * bucket_size;
+        *histogram.entry(lower).or_insert(0) += 1;
+    }
+    Ok(histogram)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALICE: &str = "ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3";
+    const BOB: &str = "9jLkNAaW9E47LQMHvjohy2uAAyr1331bAxgJKFRU7wF6";
+    const CAROL: &str = "68GLr8rYqhXTRgYuH5MN7BeswuPxjeEZRLMzunr9JQCt";
+    const DAVE: &str = "7bDXTe5fFehXPtVMMh9cL5hxcjNenk8g34eCNRTiuBTs";
+
+    /// Tests counting deposits and withdrawals across several wallets
+    #[test]
+    fn test_count_by_type() {
+        let transactions = vec![
+            Transaction::new(TransactionType::Deposit, ALICE, 100).unwrap(),
+            Transaction::new(TransactionType::Deposit, BOB, 100).unwrap(),
+            Transaction::new(TransactionType::Withdrawal, ALICE, 10).unwrap(),
+            Transaction::new(TransactionType::Deposit, ALICE, 5).unwrap(),
+        ];
+
+        let counts = count_by_type(&transactions);
+        assert_eq!(counts.len(), 2);
+        assert_eq!(counts[&TransactionType::Deposit], 3);
+        assert_eq!(counts[&TransactionType::Withdrawal], 1);
+    }
+
+    /// Tests inflow, outflow, and net for a mixed batch across wallets
+    #[test]
+    fn test_net_flow() {
+        let transactions = vec![
+            Transaction::new(TransactionType::Deposit, ALICE, 500).unwrap(),
+            Transaction::new(TransactionType::Deposit, BOB, 300).unwrap(),
+            Transaction::new(TransactionType::Withdrawal, ALICE, 200).unwrap(),
+            Transaction::new(TransactionType::Transfer { to: ALICE.to_string() }, BOB, 100).unwrap(),
+            Transaction::new(TransactionType::Withdrawal, BOB, 50).unwrap(),
+        ];
+
+        assert_eq!(
+            net_flow(&transactions).unwrap(),
+            NetFlow {
+                total_in: 800,
+                total_out: 250,
+                net: 550,
+            }
+        );
+    }
+
+    /// Tests that an overflowing total is reported instead of wrapping
+    #[test]
+    fn test_net_flow_overflow() {
+        let transactions = vec![
+            Transaction::new(TransactionType::Deposit, ALICE, i64::MAX).unwrap(),
+            Transaction::new(TransactionType::Deposit, BOB, 1).unwrap(),
+        ];
+        assert!(matches!(net_flow(&transactions), Err(TransactionError::BalanceOverflow(_))));
+    }
+
+    /// Tests picking the two richest of four wallets, breaking a tie by address
+    #[test]
+    fn test_top_wallets_by_balance() {
+        let transactions = vec![
+            Transaction::new(TransactionType::Deposit, ALICE, 300).unwrap(),
+            Transaction::new(TransactionType::Deposit, BOB, 100).unwrap(),
+            Transaction::new(TransactionType::Deposit, CAROL, 500).unwrap(),
+            Transaction::new(TransactionType::Deposit, DAVE, 500).unwrap(),
+            Transaction::new(TransactionType::Withdrawal, ALICE, 250).unwrap(),
+        ];
+
+        let top = top_wallets_by_balance(&transactions, 2).unwrap();
+        assert_eq!(top, vec![(CAROL.to_string(), 500), (DAVE.to_string(), 500)]);
+        assert_eq!(top_wallets_by_balance(&transactions, 10).unwrap().len(), 4);
+    }
+
+    /// Tests categorizing withdrawals with two rules and one unmatched withdrawal
+    #[test]
+    fn test_categorize() {
+        let rules = vec![
+            ("coffee".to_string(), "food".to_string()),
+            ("rent".to_string(), "housing".to_string()),
+        ];
+        let transactions = vec![
+            Transaction::new(TransactionType::Withdrawal, ALICE, 5).unwrap().with_memo("Morning coffee"),
+            Transaction::new(TransactionType::Withdrawal, ALICE, 1_000).unwrap().with_memo("March rent"),
+            Transaction::new(TransactionType::Withdrawal, BOB, 7).unwrap().with_memo("coffee beans"),
+            Transaction::new(TransactionType::Withdrawal, ALICE, 42).unwrap().with_memo("concert tickets"),
+            Transaction::new(TransactionType::Deposit, ALICE, 500).unwrap().with_memo("rent refund"),
+        ];
+
+        let totals = categorize(&transactions, &rules);
+        assert_eq!(totals.len(), 3);
+        assert_eq!(totals["food"], 12);
+        assert_eq!(totals["housing"], 1_000);
+        assert_eq!(totals["uncategorized"], 42);
+    }
+
+    /// Tests that only wallets with activity in the window are listed
+    #[test]
+    fn test_active_wallets_since() {
+        let transactions = vec![
+            Transaction::new(TransactionType::Deposit, ALICE, 100).unwrap().with_timestamp(100),
+            Transaction::new(TransactionType::Deposit, BOB, 100).unwrap().with_timestamp(150),
+            Transaction::new(TransactionType::Deposit, CAROL, 100).unwrap().with_timestamp(200),
+            Transaction::new(TransactionType::Withdrawal, ALICE, 10).unwrap().with_timestamp(250),
+        ];
+
+        let active = active_wallets_since(&transactions, 200);
+        assert_eq!(active, HashSet::from([ALICE.to_string(), CAROL.to_string()]));
+    }
+
+    /// Tests that transfers in both directions between two wallets are netted
+    #[test]
+    fn test_net_settlements() {
+        let transactions = vec![
+            Transaction::new(TransactionType::Transfer { to: BOB.to_string() }, ALICE, 100).unwrap(),
+            Transaction::new(TransactionType::Transfer { to: ALICE.to_string() }, BOB, 30).unwrap(),
+            Transaction::new(TransactionType::Transfer { to: ALICE.to_string() }, BOB, 20).unwrap(),
+            Transaction::new(TransactionType::Transfer { to: CAROL.to_string() }, BOB, 5).unwrap(),
+            Transaction::new(TransactionType::Transfer { to: BOB.to_string() }, ALICE, 999)
+                .unwrap()
+                .with_status(TransactionStatus::Failed),
+            Transaction::new(TransactionType::Deposit, ALICE, 500).unwrap(),
+        ];
+
+        let settlements = net_settlements(&transactions);
+        // BOB sorts before ALICE, so ALICE's net payment to BOB is negative under this key
+        assert_eq!(
+            settlements,
+            HashMap::from([
+                ((BOB.to_string(), ALICE.to_string()), -50),
+                ((CAROL.to_string(), BOB.to_string()), -5),
+            ])
+        );
+    }
+
+    /// Tests that only the wallet ending below zero is reported
+    #[test]
+    fn test_negative_balance_wallets() {
+        let transactions = vec![
+            Transaction::new(TransactionType::Deposit, ALICE, 100).unwrap(),
+            Transaction::new(TransactionType::Withdrawal, BOB, 40).unwrap(),
+            Transaction::new(TransactionType::Deposit, BOB, 10).unwrap(),
+            Transaction::new(TransactionType::Withdrawal, CAROL, 5).unwrap(),
+            Transaction::new(TransactionType::Deposit, CAROL, 5).unwrap(),
+        ];
+
+        assert_eq!(
+            negative_balance_wallets(&transactions).unwrap(),
+            HashMap::from([(BOB.to_string(), -30)])
+        );
+    }
+
+    /// Tests that only wallets crossing the threshold are reported, with the side they end on
+    #[test]
+    fn test_threshold_alerts() {
+        let transactions = vec![
+            Transaction::new(TransactionType::Deposit, ALICE, 5_000).unwrap(),
+            Transaction::new(TransactionType::Deposit, BOB, 999).unwrap(),
+            Transaction::new(TransactionType::Deposit, CAROL, 1_000).unwrap(),
+            Transaction::new(TransactionType::Withdrawal, ALICE, 4_500).unwrap(),
+        ];
+
+        // Bob never reaches the threshold, and Alice crosses it twice to end below
+        let alerts = threshold_alerts(&transactions, 1_000).unwrap();
+        let summary: Vec<(&str, i64, bool)> = alerts.iter().map(|a| (a.wallet.as_str(), a.balance, a.above)).collect();
+        assert_eq!(summary, vec![(CAROL, 1_000, true), (ALICE, 500, false)]);
+    }
+
+    /// Tests that wallets staying below the threshold throughout raise no alert
+    #[test]
+    fn test_threshold_alerts_no_crossing() {
+        let transactions = vec![
+            Transaction::new(TransactionType::Deposit, ALICE, 500).unwrap(),
+            Transaction::new(TransactionType::Transfer { to: BOB.to_string() }, ALICE, 200).unwrap(),
+            Transaction::new(TransactionType::Deposit, CAROL, 999).unwrap(),
+        ];
+
+        assert!(threshold_alerts(&transactions, 1_000).unwrap().is_empty());
+    }
+
+    /// Tests that a withdrawal larger than any deposit is picked out
+    #[test]
+    fn test_largest_transaction() {
+        let transactions = vec![
+            Transaction::new(TransactionType::Deposit, ALICE, 100).unwrap(),
+            Transaction::new(TransactionType::Withdrawal, ALICE, 250).unwrap(),
+            Transaction::new(TransactionType::Deposit, ALICE, 200).unwrap(),
+            Transaction::new(TransactionType::Deposit, BOB, 1_000).unwrap(),
+            Transaction::new(TransactionType::Withdrawal, ALICE, 900)
+                .unwrap()
+                .with_status(TransactionStatus::Failed),
+        ];
+
+        assert_eq!(largest_transaction(ALICE, &transactions), Some(transactions[1].clone()));
+        assert_eq!(largest_transaction(CAROL, &transactions), None);
+    }
+
+    /// Tests bucketing amounts into buckets of 100, and rejecting an empty bucket size
+    #[test]
+    fn test_amount_histogram() {
+        let transactions: Vec<Transaction> = [1, 99, 100, 150, 199, 420]
+            .into_iter()
+            .map(|amount| Transaction::new(TransactionType::Deposit, ALICE, amount).unwrap())
+            .collect();
+
+        assert_eq!(
+            amount_histogram(&transactions, 100).unwrap(),
+            BTreeMap::from([(0, 2), (100, 3), (400, 1)])
+        );
+        assert!(matches!(
+            amount_histogram(&transactions, 0),
+            Err(TransactionError::InvalidBucketSize(0))
+        ));
+    }
+
+    /// Tests that a negative amount is rejected rather than overflowing its bucket
+    #[test]
+    fn test_amount_histogram_negative_amount() {
+        let mut transactions = vec![Transaction::new(TransactionType::Deposit, ALICE, 100).unwrap(); 2];
+        transactions[1].amount = i64::MIN;
+
+        assert!(matches!(
+            amount_histogram(&transactions, 3),
+            Err(TransactionError::InvalidTransaction { index: 1, source })
+                if matches!(*source, TransactionError::NegativeAmount(_))
+        ));
+    }
+}
@@ -0,0 +1,2145 @@
+use std::borrow::Borrow;
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use crate::address::{normalize_wallet_address, validate_address, validate_mint, AddressValidator, WalletAddress};
+use crate::config::ValidationConfig;
+use crate::dedup::unique_by_signature;
+use crate::error::TransactionError;
+use crate::fees::FeeSchedule;
+use crate::transaction::{validate_amount, validate_fee, Transaction, TransactionStatus, TransactionType};
+
+/// Calculates the current balance for a given wallet address based on its transaction history
+/// it uses the Solana address format
+///
+/// Only native SOL transactions are counted; SPL token transactions are tracked
+/// separately by [`calculate_all_balances`]. Transfers count against the sending
+/// wallet and towards the receiving wallet. Any fee is deducted from the wallet
+/// that submitted the transaction, whatever its type. Pending transactions are
+/// included and failed ones skipped, as in [`calculate_available_balance`].
+///
+/// A wallet that no transaction involves is reported as `NoTransactions`, even when
+/// other wallets have transactions, rather than given a balance of zero. The running
+/// balance may exceed the range of `i64` part-way through the history, as long as the
+/// final balance fits.
+///
+/// # Arguments
+///
+/// * `wallet_address` - The address of the wallet to calculate the balance for
+/// * `transactions` - A slice of transactions to process
+///
+/// # Returns
+///
+/// * `Ok(i64)` - The calculated balance if successful
+/// * `Err(TransactionError)` - If there's an error processing the transactions
+///
+pub fn calculate_wallet_balance(
+    wallet_address: &WalletAddress,
+    transactions: &[Transaction],
+) -> Result<i64, TransactionError> {
+    calculate_wallet_balance_checked(wallet_address.as_str(), transactions, true)
+}
+
+/// Calculates the current balance for a given wallet address, optionally rejecting overdrafts
+///
+/// # Arguments
+///
+/// * `wallet_address` - The address of the wallet to calculate the balance for
+/// * `transactions` - A slice of transactions to process
+/// * `allow_negative` - Whether withdrawals may push the running balance below zero
+///
+/// # Returns
+///
+/// * `Ok(i64)` - The calculated balance if successful
+/// * `Err(TransactionError)` - If there's an error processing the transactions, or if
+///   `allow_negative` is false and a withdrawal would overdraw the wallet
+///
+pub fn calculate_wallet_balance_checked(
+    wallet_address: &str,
+    transactions: &[Transaction],
+    allow_negative: bool,
+) -> Result<i64, TransactionError> {
+    let rules = BalanceRules {
+        reject_overdraft: !allow_negative,
+        ..BalanceRules::default()
+    };
+    fold_wallet_balance(wallet_address, transactions, &rules)
+}
+
+/// Calculates the current balance for a given wallet address, enforcing a minimum balance
+///
+/// Solana accounts must hold a minimum balance to stay rent-exempt, so every withdrawal is
+/// checked against the floor as it is applied, not just the final balance. Deposits that
+/// leave the balance below the floor (for instance while funding a new account) are allowed.
+///
+/// # Arguments
+///
+/// * `wallet_address` - The address of the wallet to calculate the balance for
+/// * `transactions` - A slice of transactions to process
+/// * `min_balance` - The lowest balance a withdrawal may leave behind
+///
+/// # Returns
+///
+/// * `Ok(i64)` - The calculated balance if successful
+/// * `Err(TransactionError)` - `BelowRentExempt` if a withdrawal would drop the running balance
+///   below `min_balance`, or any error from processing the transactions
+///
+pub fn calculate_wallet_balance_with_floor(
+    wallet_address: &str,
+    transactions: &[Transaction],
+    min_balance: i64,
+) -> Result<i64, TransactionError> {
+    let rules = BalanceRules {
+        min_balance: Some(min_balance),
+        ..BalanceRules::default()
+    };
+    fold_wallet_balance(wallet_address, transactions, &rules)
+}
+
+/// Calculates the current balance for a given wallet address, first checking that every
+/// transaction in the slice has a well-formed address
+///
+/// Unlike [`calculate_wallet_balance`], which silently skips transactions belonging to
+/// other wallets, this catches garbage addresses anywhere in the data.
+///
+/// # Arguments
+///
+/// * `wallet_address` - The address of the wallet to calculate the balance for
+/// * `transactions` - A slice of transactions to process
+///
+/// # Returns
+///
+/// * `Ok(i64)` - The calculated balance if successful
+/// * `Err(TransactionError)` - `InvalidTransaction` locating the first malformed address in the
+///   slice, or any error from processing the transactions
+///
+pub fn calculate_wallet_balance_strict(wallet_address: &str, transactions: &[Transaction]) -> Result<i64, TransactionError> {
+    for (index, tx) in transactions.iter().enumerate() {
+        validate_address(&tx.wallet_address).map_err(|e| locate_error(index, e))?;
+        if let TransactionType::Transfer { to } = &tx.transaction_type {
+            validate_address(to).map_err(|e| locate_error(index, e))?;
+        }
+    }
+    calculate_wallet_balance(&WalletAddress::parse(wallet_address)?, transactions)
+}
+
+/// Calculates the gross balance for a given wallet address, before network costs
+///
+/// Behaves like [`calculate_wallet_balance`], except that each transaction's fee is
+/// ignored and standalone [`TransactionType::Fee`] transactions are skipped.
+///
+/// # Arguments
+///
+/// * `wallet_address` - The address of the wallet to calculate the balance for
+/// * `transactions` - A slice of transactions to process
+///
+/// # Returns
+///
+/// * `Ok(i64)` - The balance without any fees deducted
+/// * `Err(TransactionError)` - If there's an error processing the transactions
+///
+pub fn calculate_wallet_balance_gross(wallet_address: &str, transactions: &[Transaction]) -> Result<i64, TransactionError> {
+    let rules = BalanceRules {
+        exclude_fees: true,
+        ..BalanceRules::default()
+    };
+    fold_wallet_balance(wallet_address, transactions, &rules)
+}
+
+/// Calculates the current balance for a given wallet address, ignoring dust
+///
+/// Behaves like [`calculate_wallet_balance`], except that transactions whose amount is
+/// below `dust_threshold` are skipped along with their fees, as if removed by
+/// [`filter_dust`](crate::filter_dust). A wallet whose history is all dust has a balance of zero.
+///
+/// # Arguments
+///
+/// * `wallet_address` - The address of the wallet to calculate the balance for
+/// * `transactions` - A slice of transactions to process
+/// * `dust_threshold` - The smallest amount counted towards the balance
+///
+/// # Returns
+///
+/// * `Ok(i64)` - The balance without dust transactions
+/// * `Err(TransactionError)` - If there's an error processing the transactions
+///
+pub fn calculate_wallet_balance_without_dust(
+    wallet_address: &str,
+    transactions: &[Transaction],
+    dust_threshold: i64,
+) -> Result<i64, TransactionError> {
+    let rules = BalanceRules {
+        dust_threshold: Some(dust_threshold),
+        ..BalanceRules::default()
+    };
+    fold_wallet_balance(wallet_address, transactions, &rules)
+}
+
+/// Calculates the current balance for a given wallet address on any chain
+///
+/// Behaves like [`calculate_wallet_balance`], but the wallet address is checked with
+/// `validator` instead of the Solana address format, so histories from other chains can be
+/// processed. Such transactions cannot pass [`Transaction::new`] and are typically
+/// deserialized from an external source.
+///
+/// # Arguments
+///
+/// * `wallet_address` - The address of the wallet to calculate the balance for
+/// * `transactions` - A slice of transactions to process
+/// * `validator` - The address format to accept, e.g. [`EthereumValidator`](crate::EthereumValidator)
+///
+/// # Returns
+///
+/// * `Ok(i64)` - The calculated balance if successful
+/// * `Err(TransactionError)` - `InvalidWalletAddress` if `validator` rejects the trimmed address,
+///   or any error from processing the transactions
+///
+pub fn calculate_wallet_balance_with_validator(
+    wallet_address: &str,
+    transactions: &[Transaction],
+    validator: &dyn AddressValidator,
+) -> Result<i64, TransactionError> {
+    let wallet_address = wallet_address.trim();
+    if !validator.validate(wallet_address) {
+        return Err(TransactionError::InvalidWalletAddress(wallet_address.to_string()));
+    }
+    fold_balance(wallet_address, indexed_for_wallet(wallet_address, transactions), &BalanceRules::default())
+}
+
+/// Calculates the current balance for a given wallet address, rejecting oversized transactions
+///
+/// This catches fat-finger errors: every transaction is checked against the ceiling before it
+/// is applied to the balance.
+///
+/// # Arguments
+///
+/// * `wallet_address` - The address of the wallet to calculate the balance for
+/// * `transactions` - A slice of transactions to process
+/// * `max_amount` - The largest amount a single transaction may carry
+///
+/// # Returns
+///
+/// * `Ok(i64)` - The calculated balance if successful
+/// * `Err(TransactionError)` - `AmountExceedsLimit` for the first transaction over `max_amount`,
+///   or any error from processing the transactions
+///
+pub fn calculate_wallet_balance_with_limit(
+    wallet_address: &str,
+    transactions: &[Transaction],
+    max_amount: i64,
+) -> Result<i64, TransactionError> {
+    let rules = BalanceRules {
+        max_amount: Some(max_amount),
+        ..BalanceRules::default()
+    };
+    fold_wallet_balance(wallet_address, transactions, &rules)
+}
+
+/// Calculates the current balance for a given wallet address, capping how many transactions it may have
+///
+/// This guards against spammy imports: the wallet's transactions are counted before any
+/// of them is applied, so an oversized history is rejected without being processed.
+///
+/// # Arguments
+///
+/// * `wallet_address` - The address of the wallet to calculate the balance for
+/// * `transactions` - A slice of transactions to process
+/// * `max_tx` - The most transactions the wallet may be involved in
+///
+/// # Returns
+///
+/// * `Ok(i64)` - The calculated balance if successful
+/// * `Err(TransactionError)` - `TooManyTransactions` if more than `max_tx` transactions involve
+///   the wallet, or any error from processing the transactions
+///
+pub fn calculate_wallet_balance_capped(
+    wallet_address: &str,
+    transactions: &[Transaction],
+    max_tx: usize,
+) -> Result<i64, TransactionError> {
+    let wallet_address = normalize_wallet_address(wallet_address)?;
+    let count = indexed_for_wallet(&wallet_address, transactions).count();
+    if count > max_tx {
+        return Err(TransactionError::TooManyTransactions {
+            wallet: wallet_address,
+            count,
+            max: max_tx,
+        });
+    }
+    fold_balance(&wallet_address, indexed_for_wallet(&wallet_address, transactions), &BalanceRules::default())
+}
+
+/// Calculates the current balance for a given wallet address, blocking activity on frozen wallets
+///
+/// Deposits and incoming transfers are still credited to a frozen wallet; failed
+/// transactions moved no funds and are not flagged.
+///
+/// # Arguments
+///
+/// * `wallet_address` - The address of the wallet to calculate the balance for
+/// * `transactions` - A slice of transactions to process
+/// * `frozen_wallets` - The addresses of wallets that may not send funds
+///
+/// # Returns
+///
+/// * `Ok(i64)` - The calculated balance if successful
+/// * `Err(TransactionError)` - `WalletFrozen` if the wallet is frozen and has a withdrawal or
+///   outgoing transfer, or any error from processing the transactions
+///
+pub fn calculate_wallet_balance_with_freeze(
+    wallet_address: &str,
+    transactions: &[Transaction],
+    frozen_wallets: &HashSet<String>,
+) -> Result<i64, TransactionError> {
+    let rules = BalanceRules {
+        frozen: frozen_wallets.contains(wallet_address.trim()),
+        ..BalanceRules::default()
+    };
+    fold_wallet_balance(wallet_address, transactions, &rules)
+}
+
+/// Calculates the current balance for a given wallet address, applying configurable rules
+///
+/// Each transaction's amount is checked against `config.min_amount` and `config.max_amount`
+/// before it is applied. Withdrawals are then checked against `config.min_balance` and,
+/// unless `config.allow_negative` is set, against overdrawing the wallet.
+///
+/// # Arguments
+///
+/// * `wallet_address` - The address of the wallet to calculate the balance for
+/// * `transactions` - A slice of transactions to process
+/// * `config` - The rules to apply, e.g. loaded with [`ValidationConfig::from_toml`]
+///
+/// # Returns
+///
+/// * `Ok(i64)` - The calculated balance if successful
+/// * `Err(TransactionError)` - The error for the first rule broken, or any error from
+///   processing the transactions
+///
+pub fn calculate_wallet_balance_with_config(
+    wallet_address: &str,
+    transactions: &[Transaction],
+    config: &ValidationConfig,
+) -> Result<i64, TransactionError> {
+    let rules = BalanceRules {
+        reject_overdraft: !config.allow_negative,
+        min_balance: config.min_balance,
+        min_amount: config.min_amount,
+        max_amount: config.max_amount,
+        ..BalanceRules::default()
+    };
+    fold_wallet_balance(wallet_address, transactions, &rules)
+}
+
+/// Calculates the current balance for a given wallet address, charging a fee per transaction
+///
+/// Each transaction the wallet submitted is charged the fee its kind costs in `schedule`,
+/// on top of any fee recorded on the transaction itself. Incoming transfers and failed
+/// transactions are not charged.
+///
+/// # Arguments
+///
+/// * `wallet_address` - The address of the wallet to calculate the balance for
+/// * `transactions` - A slice of transactions to process
+/// * `schedule` - The fee charged for each kind of transaction
+///
+/// # Returns
+///
+/// * `Ok(i64)` - The calculated balance, net of scheduled fees, if successful
+/// * `Err(TransactionError)` - If there's an error processing the transactions
+///
+pub fn calculate_wallet_balance_with_schedule(
+    wallet_address: &str,
+    transactions: &[Transaction],
+    schedule: &FeeSchedule,
+) -> Result<i64, TransactionError> {
+    let rules = BalanceRules {
+        fee_schedule: Some(schedule),
+        ..BalanceRules::default()
+    };
+    fold_wallet_balance(wallet_address, transactions, &rules)
+}
+
+/// Calculates the current balance for a given wallet address, ignoring duplicate transactions
+///
+/// Only the first transaction with a given signature is counted, so histories merged from
+/// several providers are not double-counted. See [`dedup_transactions`](crate::dedup_transactions).
+///
+/// # Arguments
+///
+/// * `wallet_address` - The address of the wallet to calculate the balance for
+/// * `transactions` - A slice of transactions that may contain duplicates
+///
+/// # Returns
+///
+/// * `Ok(i64)` - The calculated balance if successful
+/// * `Err(TransactionError)` - If there's an error processing the transactions
+///
+pub fn calculate_wallet_balance_dedup(wallet_address: &str, transactions: &[Transaction]) -> Result<i64, TransactionError> {
+    let wallet_address = normalize_wallet_address(wallet_address)?;
+    let unique = unique_by_signature(transactions).filter(|(_, tx)| is_for_wallet(tx, &wallet_address));
+    fold_balance(&wallet_address, unique, &BalanceRules::default())
+}
+
+/// Calculates the balance for a given wallet address, counting only sufficiently confirmed transactions
+///
+/// This models commitment levels: a low threshold gives a "processed" balance that includes
+/// recent transactions, while a high one gives a "finalized" balance that cannot be rolled back.
+///
+/// # Arguments
+///
+/// * `wallet_address` - The address of the wallet to calculate the balance for
+/// * `transactions` - A slice of transactions to process
+/// * `min` - The fewest confirmations a transaction needs to be counted
+///
+/// # Returns
+///
+/// * `Ok(i64)` - The balance over transactions with at least `min` confirmations
+/// * `Err(TransactionError)` - If there's an error processing the transactions
+///
+pub fn calculate_balance_min_confirmations(
+    wallet_address: &str,
+    transactions: &[Transaction],
+    min: u32,
+) -> Result<i64, TransactionError> {
+    let rules = BalanceRules {
+        min_confirmations: min,
+        ..BalanceRules::default()
+    };
+    fold_wallet_balance(wallet_address, transactions, &rules)
+}
+
+/// Calculates the confirmed balance for a given wallet address, ignoring pending and failed transactions
+///
+/// # Arguments
+///
+/// * `wallet_address` - The address of the wallet to calculate the balance for
+/// * `transactions` - A slice of transactions to process
+///
+/// # Returns
+///
+/// * `Ok(i64)` - The confirmed balance if successful
+/// * `Err(TransactionError)` - If there's an error processing the transactions
+///
+pub fn calculate_confirmed_balance(wallet_address: &str, transactions: &[Transaction]) -> Result<i64, TransactionError> {
+    let rules = BalanceRules {
+        confirmed_only: true,
+        ..BalanceRules::default()
+    };
+    fold_wallet_balance(wallet_address, transactions, &rules)
+}
+
+/// Calculates the available balance for a given wallet address, including pending transactions
+///
+/// Failed transactions are still ignored.
+///
+/// # Arguments
+///
+/// * `wallet_address` - The address of the wallet to calculate the balance for
+/// * `transactions` - A slice of transactions to process
+///
+/// # Returns
+///
+/// * `Ok(i64)` - The available balance if successful
+/// * `Err(TransactionError)` - If there's an error processing the transactions
+///
+pub fn calculate_available_balance(wallet_address: &str, transactions: &[Transaction]) -> Result<i64, TransactionError> {
+    fold_wallet_balance(wallet_address, transactions, &BalanceRules::default())
+}
+
+/// Calculates the current balance for a given wallet address from a stream of transactions
+///
+/// Transactions are consumed lazily one at a time, so the history never has to be
+/// held in memory as a slice.
+///
+/// # Arguments
+///
+/// * `wallet_address` - The address of the wallet to calculate the balance for
+/// * `transactions` - Any iterator (or collection) yielding transactions
+///
+/// # Returns
+///
+/// * `Ok(i64)` - The calculated balance if successful
+/// * `Err(TransactionError)` - If there's an error processing the transactions
+///
+pub fn calculate_balance_from_iter<I: IntoIterator<Item = Transaction>>(
+    wallet_address: &str,
+    transactions: I,
+) -> Result<i64, TransactionError> {
+    let wallet_address = normalize_wallet_address(wallet_address)?;
+    let matching = transactions
+        .into_iter()
+        .enumerate()
+        .filter(|(_, tx)| is_for_wallet(tx, &wallet_address));
+    fold_balance(&wallet_address, matching, &BalanceRules::default())
+}
+
+/// Selects the transactions that involve a wallet, as its source or transfer destination
+///
+/// Both native SOL and SPL token transactions are yielded, in input order.
+///
+/// # Arguments
+///
+/// * `wallet` - The address of the wallet to select transactions for
+/// * `transactions` - A slice of transactions to search
+///
+/// # Returns
+///
+/// * `impl Iterator<Item = &Transaction>` - The transactions involving `wallet`
+///
+pub fn transactions_for_wallet<'a>(wallet: &str, transactions: &'a [Transaction]) -> impl Iterator<Item = &'a Transaction> {
+    indexed_for_wallet(wallet, transactions).map(|(_, tx)| tx)
+}
+
+/// Selects the transactions that involve a wallet, along with each one's index in the slice
+pub(crate) fn indexed_for_wallet<'a>(wallet: &str, transactions: &'a [Transaction]) -> impl Iterator<Item = (usize, &'a Transaction)> {
+    let wallet = wallet.to_string();
+    transactions
+        .iter()
+        .enumerate()
+        .filter(move |(_, tx)| is_for_wallet(tx, &wallet))
+}
+
+/// Checks whether a transaction involves `wallet`, logging a warning when it is skipped
+fn is_for_wallet(tx: &Transaction, wallet: &str) -> bool {
+    let involved = tx.involves(wallet);
+    #[cfg(feature = "tracing")]
+    if !involved {
+        tracing::warn!(wallet, transaction_wallet = %tx.wallet_address, "skipping transaction for another wallet");
+    }
+    involved
+}
+
+/// Optional checks applied to each transaction while folding a wallet's balance
+#[derive(Debug, Default)]
+struct BalanceRules<'a> {
+    /// Reject withdrawals that push the running balance below zero
+    reject_overdraft: bool,
+    /// Reject withdrawals that leave less than this running balance
+    min_balance: Option<i64>,
+    /// Skip pending transactions, counting only confirmed ones
+    confirmed_only: bool,
+    /// Skip transactions with fewer confirmations than this
+    min_confirmations: u32,
+    /// Reject transactions whose amount is below this minimum
+    min_amount: Option<i64>,
+    /// Reject transactions whose amount exceeds this ceiling
+    max_amount: Option<i64>,
+    /// Charge a fixed fee per submitted transaction according to its kind
+    fee_schedule: Option<&'a FeeSchedule>,
+    /// Ignore network fees, both per-transaction and standalone, giving the gross balance
+    exclude_fees: bool,
+    /// Reject withdrawals and outgoing transfers, as for a wallet flagged by compliance
+    frozen: bool,
+    /// Skip transactions whose amount is below this threshold
+    dust_threshold: Option<i64>,
+}
+
+/// Tracks which wallets have had a transaction, so that an opening balance can only come first
+///
+/// Wallets are tracked per token, keyed by `(address, mint)` as in [`calculate_all_balances`],
+/// so an SPL token transaction does not stop a native opening balance from following it.
+#[derive(Debug, Default)]
+pub(crate) struct OpeningBalanceGuard {
+    /// The `(address, mint)` pairs that have had a transaction
+    seen: HashSet<(String, Option<String>)>,
+}
+
+impl OpeningBalanceGuard {
+    /// Creates a guard for which each of `wallets` already has native SOL history
+    pub(crate) fn with_native_history<'a>(wallets: impl IntoIterator<Item = &'a String>) -> OpeningBalanceGuard {
+        OpeningBalanceGuard {
+            seen: wallets.into_iter().map(|wallet| (wallet.clone(), None)).collect(),
+        }
+    }
+
+    /// Records that `tx` involves `wallet`, rejecting an opening balance that is not the wallet's first transaction
+    pub(crate) fn record(&mut self, tx: &Transaction, wallet: &str) -> Result<(), TransactionError> {
+        let first = self.seen.insert((wallet.to_string(), tx.mint.clone()));
+        if !first && tx.transaction_type == TransactionType::OpeningBalance {
+            return Err(TransactionError::MisplacedOpeningBalance(wallet.to_string()));
+        }
+        Ok(())
+    }
+}
+
+/// Folds a wallet's balance over a slice of transactions, enforcing `rules`
+fn fold_wallet_balance(
+    wallet_address: &str,
+    transactions: &[Transaction],
+    rules: &BalanceRules<'_>,
+) -> Result<i64, TransactionError> {
+    // Normalize and validate wallet address and Solana address format
+    let wallet_address = normalize_wallet_address(wallet_address)?;
+    fold_balance(&wallet_address, indexed_for_wallet(&wallet_address, transactions), rules)
+}
+
+/// Folds a wallet's balance over owned or borrowed transactions already selected for it, enforcing `rules`
+///
+/// Each transaction is paired with its index in the caller's input, which invalid
+/// transactions are reported with. Returns `NoTransactions` if nothing was selected,
+/// so a wallet absent from the history is reported rather than given a balance of zero.
+///
+/// The running balance is accumulated as an `i128`, so it may leave the range of `i64`
+/// part-way through; only the final balance must fit, or `BalanceOverflow` is returned.
+fn fold_balance<T: Borrow<Transaction>>(
+    wallet_address: &str,
+    transactions: impl IntoIterator<Item = (usize, T)>,
+    rules: &BalanceRules<'_>,
+) -> Result<i64, TransactionError> {
+    let mut seen_any = false;
+    let mut opening = OpeningBalanceGuard::default();
+    let balance = transactions
+        .into_iter()
+        .inspect(|_| seen_any = true)
+        .filter(|(_, tx)| tx.borrow().mint.is_none())
+        .try_fold(0i128, |acc, (index, tx)| {
+            let tx = tx.borrow();
+
+            // Only the wallet's first native transaction may seed its balance, even one the rules skip
+            opening.record(tx, wallet_address).map_err(|e| locate_error(index, e))?;
+            let skipped = (rules.confirmed_only && tx.status == TransactionStatus::Pending)
+                || tx.confirmations < rules.min_confirmations
+                || (rules.exclude_fees && tx.transaction_type == TransactionType::Fee)
+                || rules.dust_threshold.is_some_and(|threshold| tx.amount < threshold);
+            if skipped {
+                return Ok(acc);
+            }
+
+            let without_fee;
+            let tx = if rules.exclude_fees && tx.fee.is_some() {
+                without_fee = Transaction { fee: None, ..tx.clone() };
+                &without_fee
+            } else {
+                tx
+            };
+
+            // A frozen wallet may still receive funds, but not send them
+            if rules.frozen && tx.status != TransactionStatus::Failed {
+                let outgoing = match &tx.transaction_type {
+                    TransactionType::Withdrawal => true,
+                    TransactionType::Transfer { to } => to != wallet_address,
+                    _ => false,
+                };
+                if outgoing {
+                    return Err(TransactionError::WalletFrozen(wallet_address.to_string()));
+                }
+            }
+
+            // Reject undersized and oversized transactions before they touch the balance
+            if let Some(min) = rules.min_amount {
+                if tx.amount < min {
+                    return Err(TransactionError::AmountBelowMinimum { amount: tx.amount, min });
+                }
+            }
+            if let Some(max) = rules.max_amount {
+                if tx.amount > max {
+                    return Err(TransactionError::AmountExceedsLimit { amount: tx.amount, max });
+                }
+            }
+            // Apply the transaction's change to the wide accumulator; an opening balance is
+            // always first, so its change from zero is the balance it sets
+            let delta = transaction_delta(tx, wallet_address).map_err(|e| locate_error(index, e))?;
+            let mut balance = acc + delta;
+
+            // Charge the scheduled fee to the wallet that submitted the transaction
+            if let Some(schedule) = rules.fee_schedule {
+                if tx.wallet_address == wallet_address && tx.status != TransactionStatus::Failed {
+                    balance -= i128::from(schedule.fee_for(&tx.transaction_type));
+                }
+            }
+
+            #[cfg(feature = "tracing")]
+            tracing::debug!(wallet = wallet_address, balance, "processed transaction");
+
+            // Reject the debit, including any fee, that would overdraw the wallet
+            if rules.reject_overdraft && balance < acc && balance < 0 {
+                return Err(TransactionError::InsufficientFunds {
+                    wallet: wallet_address.to_string(),
+                    balance: saturate(acc),
+                    attempted: saturate(acc - balance),
+                });
+            }
+
+            // Reject the withdrawal that would drop the wallet below its floor
+            if let Some(min) = rules.min_balance {
+                if balance < acc && balance < i128::from(min) {
+                    return Err(TransactionError::BelowRentExempt {
+                        wallet: wallet_address.to_string(),
+                        balance: saturate(balance),
+                        min,
+                    });
+                }
+            }
+            Ok(balance)
+        })?;
+
+    // Check if any transactions involve the wallet
+    if !seen_any {
+        return Err(TransactionError::NoTransactions(wallet_address.to_string()));
+    }
+    i64::try_from(balance).map_err(|_| TransactionError::BalanceOverflow(wallet_address.to_string()))
+}
+
+/// Clamps a wide running balance into an `i64` for reporting in an error
+fn saturate(balance: i128) -> i64 {
+    i64::try_from(balance).unwrap_or(if balance < 0 { i64::MIN } else { i64::MAX })
+}
+
+/// Attaches the index of the offending transaction to a validation error, keeping it as the source
+///
+/// Errors about the balance as a whole, such as an overdraft, are returned unchanged.
+pub(crate) fn locate_error(index: usize, error: TransactionError) -> TransactionError {
+    match error {
+        TransactionError::ZeroAmount
+        | TransactionError::NegativeAmount(_)
+        | TransactionError::NegativeFee(_)
+        | TransactionError::InvalidWalletAddress(_)
+        | TransactionError::InvalidMintAddress(_)
+        | TransactionError::SelfTransfer(_)
+        | TransactionError::MisplacedOpeningBalance(_)
+        | TransactionError::TimestampOutOfRange(_) => TransactionError::InvalidTransaction {
+            index,
+            source: Box::new(error),
+        },
+        error => error,
+    }
+}
+
+/// Calculates the balance of every wallet appearing in the transaction history in a single pass
+///
+/// Balances are tracked separately per token, so the same wallet can appear once
+/// for native SOL (mint `None`) and once for each SPL token mint it holds. A transfer
+/// debits its source wallet and credits its destination.
+///
+/// # Arguments
+///
+/// * `transactions` - A slice of transactions to process
+///
+/// # Returns
+///
+/// * `Ok(HashMap<(String, Option<String>), i64>)` - The balance of each wallet, keyed by `(address, mint)`
+/// * `Err(TransactionError)` - If any transaction has an invalid address, mint, or amount
+///
+pub fn calculate_all_balances(
+    transactions: &[Transaction],
+) -> Result<HashMap<(String, Option<String>), i64>, TransactionError> {
+    let mut balances = HashMap::new();
+    let mut opening = OpeningBalanceGuard::default();
+    for (index, tx) in transactions.iter().enumerate() {
+        let locate = move |e| locate_error(index, e);
+        validate_address(&tx.wallet_address).map_err(locate)?;
+        if let Some(mint) = &tx.mint {
+            validate_mint(mint).map_err(locate)?;
+        }
+
+        // Debit (or credit) the transaction's own wallet
+        opening.record(tx, &tx.wallet_address).map_err(locate)?;
+        let key = (tx.wallet_address.clone(), tx.mint.clone());
+        let balance = balances.entry(key).or_insert(0i64);
+        *balance = apply_transaction(*balance, tx, &tx.wallet_address).map_err(locate)?;
+
+        // Credit the destination of a transfer
+        if let TransactionType::Transfer { to } = &tx.transaction_type {
+            validate_address(to).map_err(locate)?;
+            opening.record(tx, to).map_err(locate)?;
+            let key = (to.clone(), tx.mint.clone());
+            let balance = balances.entry(key).or_insert(0i64);
+            *balance = apply_transaction(*balance, tx, to).map_err(locate)?;
+        }
+    }
+    Ok(balances)
+}
+
+/// Calculates the native SOL balance of every wallet appearing in the transaction history
+///
+/// This is [`calculate_all_balances`] restricted to native SOL, keyed by address alone.
+/// SPL token transactions are still validated but do not contribute.
+///
+/// # Arguments
+///
+/// * `transactions` - A slice of transactions to process
+///
+/// # Returns
+///
+/// * `Ok(HashMap<String, i64>)` - The native balance of each wallet, keyed by address
+/// * `Err(TransactionError)` - If any transaction has an invalid address, mint, or amount
+///
+pub fn calculate_native_balances(transactions: &[Transaction]) -> Result<HashMap<String, i64>, TransactionError> {
+    Ok(calculate_all_balances(transactions)?
+        .into_iter()
+        .filter_map(|((wallet, mint), balance)| mint.is_none().then_some((wallet, balance)))
+        .collect())
+}
+
+/// Calculates the native SOL balance of every wallet, sorted by address
+///
+/// This is [`calculate_native_balances`] with a deterministic order, for output that
+/// should be stable from run to run and easy to diff.
+///
+/// # Arguments
+///
+/// * `transactions` - A slice of transactions to process
+///
+/// # Returns
+///
+/// * `Ok(Vec<(String, i64)>)` - Each wallet's address and native balance, in ascending address order
+/// * `Err(TransactionError)` - If any transaction has an invalid address, mint, or amount
+///
+pub fn balances_sorted(transactions: &[Transaction]) -> Result<Vec<(String, i64)>, TransactionError> {
+    let mut balances: Vec<_> = calculate_native_balances(transactions)?.into_iter().collect();
+    balances.sort_unstable_by(|(a, _), (b, _)| a.cmp(b));
+    Ok(balances)
+}
+
+/// Calculates what a wallet's balance would be after a proposed transaction, without recording it
+///
+/// The current balance is calculated as in [`calculate_wallet_balance`], except that a
+/// wallet with no history starts from zero. A proposed transaction that does not involve
+/// the wallet leaves the balance unchanged.
+///
+/// # Arguments
+///
+/// * `wallet` - The address of the wallet to simulate
+/// * `transactions` - The wallet's existing transaction history
+/// * `proposed` - The transaction to try out
+///
+/// # Returns
+///
+/// * `Ok(i64)` - The balance the wallet would have after the proposed transaction
+/// * `Err(TransactionError)` - `InsufficientFunds` if the proposed transaction and its fee would
+///   overdraw the wallet, `MisplacedOpeningBalance` if it is an opening balance for a wallet
+///   that already has history, or any error from validating it or processing the history
+///
+pub fn simulate(wallet: &str, transactions: &[Transaction], proposed: &Transaction) -> Result<i64, TransactionError> {
+    let wallet = normalize_wallet_address(wallet)?;
+    proposed.validate()?;
+
+    let current = match fold_balance(&wallet, indexed_for_wallet(&wallet, transactions), &BalanceRules::default()) {
+        Err(TransactionError::NoTransactions(_)) => 0,
+        result => result?,
+    };
+    if !proposed.involves(&wallet) {
+        return Ok(current);
+    }
+
+    // Only the wallet's first transaction may seed its balance
+    let has_history = indexed_for_wallet(&wallet, transactions).any(|(_, tx)| tx.mint == proposed.mint);
+    if proposed.transaction_type == TransactionType::OpeningBalance && has_history {
+        return Err(TransactionError::MisplacedOpeningBalance(wallet));
+    }
+
+    // Reject a proposal that would overdraw the wallet, reporting the full debit including its fee
+    let balance = apply_transaction(current, proposed, &wallet)?;
+    if balance < 0 && balance < current {
+        return Err(TransactionError::InsufficientFunds {
+            attempted: saturate(-transaction_delta(proposed, &wallet)?),
+            wallet,
+            balance: current,
+        });
+    }
+    Ok(balance)
+}
+
+/// Projects what a wallet's balance would be if every fee were scaled by `multiplier`
+///
+/// Both per-transaction fees and standalone [`TransactionType::Fee`] transactions are
+/// scaled, so a multiplier of 2.0 models fees doubling and 0.0 models them being waived.
+/// The scaled fee total is rounded to the nearest lamport.
+///
+/// # Arguments
+///
+/// * `wallet` - The address of the wallet to project
+/// * `transactions` - The wallet's transaction history
+/// * `multiplier` - The factor to scale fees by
+///
+/// # Returns
+///
+/// * `Ok(i64)` - The balance the wallet would have under the scaled fees
+/// * `Err(TransactionError)` - `InvalidFeeMultiplier` if `multiplier` is negative or not finite,
+///   `BalanceOverflow` if the projected balance does not fit, or any error from processing the history
+///
+pub fn project_with_fee_multiplier(wallet: &str, transactions: &[Transaction], multiplier: f64) -> Result<i64, TransactionError> {
+    if !(multiplier.is_finite() && multiplier >= 0.0) {
+        return Err(TransactionError::InvalidFeeMultiplier(multiplier));
+    }
+
+    let wallet = normalize_wallet_address(wallet)?;
+
+    // The fees actually paid are the difference between the gross and net balances
+    let gross_rules = BalanceRules {
+        exclude_fees: true,
+        ..BalanceRules::default()
+    };
+    let gross = fold_wallet_balance(&wallet, transactions, &gross_rules)?;
+    let net = fold_wallet_balance(&wallet, transactions, &BalanceRules::default())?;
+    let fees = i128::from(gross) - i128::from(net);
+
+    let projected = i128::from(gross) - (fees as f64 * multiplier).round() as i128;
+    i64::try_from(projected).map_err(|_| TransactionError::BalanceOverflow(wallet))
+}
+
+/// Creates a withdrawal of a percentage of a wallet's current balance
+///
+/// The amount is rounded down to a whole lamport. A withdrawal needs a positive amount,
+/// so this fails for a wallet whose balance is not positive or whose share rounds to zero.
+///
+/// # Arguments
+///
+/// * `wallet` - The address of the wallet to withdraw from
+/// * `transactions` - The wallet's transaction history
+/// * `percent` - The share of the balance to withdraw, from 0 to 100
+///
+/// # Returns
+///
+/// * `Ok(Transaction)` - A withdrawal of `percent`% of the balance
+/// * `Err(TransactionError)` - `InvalidPercentage` if `percent` exceeds 100, or any error from
+///   calculating the balance or creating the withdrawal
+///
+pub fn percentage_withdrawal(wallet: &str, transactions: &[Transaction], percent: u8) -> Result<Transaction, TransactionError> {
+    if percent > 100 {
+        return Err(TransactionError::InvalidPercentage(percent));
+    }
+    let wallet = WalletAddress::parse(wallet)?;
+    let balance = calculate_wallet_balance(&wallet, transactions)?;
+
+    // At most 100% of an i64 balance always fits, but narrow without panicking regardless
+    let amount = i64::try_from(i128::from(balance) * i128::from(percent) / 100)
+        .map_err(|_| TransactionError::BalanceOverflow(wallet.to_string()))?;
+    Transaction::new(TransactionType::Withdrawal, wallet.as_str(), amount)
+}
+
+/// Calculates the combined native SOL balance of several wallets in a single pass
+///
+/// Each wallet is counted once however often it is listed. A transfer between two listed
+/// wallets moves funds within the group, so only its fee affects the total. Listed wallets
+/// without any transactions contribute nothing.
+///
+/// # Arguments
+///
+/// * `wallets` - The addresses of the wallets to combine
+/// * `transactions` - A slice of transactions to process
+///
+/// # Returns
+///
+/// * `Ok(i64)` - The sum of the listed wallets' balances
+/// * `Err(TransactionError)` - If a listed address is invalid or there's an error processing
+///   the transactions
+///
+pub fn aggregate_balance(wallets: &[&str], transactions: &[Transaction]) -> Result<i64, TransactionError> {
+    let wallets = wallets
+        .iter()
+        .map(|wallet| normalize_wallet_address(wallet))
+        .collect::<Result<BTreeSet<String>, TransactionError>>()?;
+
+    let mut total = 0i64;
+    let mut opening = OpeningBalanceGuard::default();
+    for (index, tx) in transactions.iter().enumerate().filter(|(_, tx)| tx.mint.is_none()) {
+        let mut involved = vec![tx.wallet_address.as_str()];
+        if let TransactionType::Transfer { to } = &tx.transaction_type {
+            involved.push(to);
+        }
+        // Sum each wallet's change, so an opening balance adds to the total rather than replacing it
+        for wallet in involved.into_iter().filter(|wallet| wallets.contains(*wallet)) {
+            opening.record(tx, wallet).map_err(|e| locate_error(index, e))?;
+            total = total
+                .checked_add(apply_transaction(0, tx, wallet).map_err(|e| locate_error(index, e))?)
+                .ok_or_else(|| TransactionError::BalanceOverflow(wallet.to_string()))?;
+        }
+    }
+    Ok(total)
+}
+
+/// Applies a single transaction to the running balance of `wallet_address`, validating its amount
+///
+/// The wallet must be involved in the transaction. Transfers are debited from their
+/// source and credited to their destination; a transfer to itself is rejected.
+/// An opening balance replaces the running balance; callers check that it comes first.
+/// The fee, if any, is paid by the transaction's own wallet. Failed transactions are skipped.
+pub(crate) fn apply_transaction(balance: i64, tx: &Transaction, wallet_address: &str) -> Result<i64, TransactionError> {
+    let delta = transaction_delta(tx, wallet_address)?;
+    let base = if tx.transaction_type == TransactionType::OpeningBalance && tx.status != TransactionStatus::Failed {
+        0
+    } else {
+        balance
+    };
+    i64::try_from(i128::from(base) + delta).map_err(|_| TransactionError::BalanceOverflow(wallet_address.to_string()))
+}
+
+/// Calculates the signed change a single transaction makes to the balance of `wallet_address`
+///
+/// This is [`apply_transaction`] applied to a balance of zero, but widened to an `i128` so an
+/// amount and fee that together exceed the range of `i64` are still represented exactly.
+pub(crate) fn transaction_delta(tx: &Transaction, wallet_address: &str) -> Result<i128, TransactionError> {
+    // Validate transaction amount and fee
+    validate_amount(tx.amount)?;
+    let fee = match tx.fee {
+        Some(fee) if tx.wallet_address == wallet_address => {
+            validate_fee(fee)?;
+            fee
+        }
+        _ => 0,
+    };
+    if tx.status == TransactionStatus::Failed {
+        return Ok(0);
+    }
+
+    let amount = i128::from(tx.amount);
+    let change = match &tx.transaction_type {
+        TransactionType::Deposit | TransactionType::StakingReward | TransactionType::OpeningBalance => amount,
+        TransactionType::Withdrawal | TransactionType::Fee => -amount,
+        TransactionType::Transfer { to } if tx.wallet_address == *to => {
+            return Err(TransactionError::SelfTransfer(to.clone()));
+        }
+        TransactionType::Transfer { .. } if tx.wallet_address == wallet_address => -amount,
+        TransactionType::Transfer { .. } => amount,
+    };
+    Ok(change - i128::from(fee))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::address::{EthereumValidator, SolanaValidator};
+
+    const ALICE: &str = "ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3";
+    const BOB: &str = "9jLkNAaW9E47LQMHvjohy2uAAyr1331bAxgJKFRU7wF6";
+    const CAROL: &str = "68GLr8rYqhXTRgYuH5MN7BeswuPxjeEZRLMzunr9JQCt";
+    const USDC: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+    const BONK: &str = "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263";
+
+    /// Parses an address known to be valid
+    fn wallet(address: &str) -> WalletAddress {
+        WalletAddress::parse(address).unwrap()
+    }
+
+    /// Tests basic balance calculation with deposit and withdrawal
+    #[test]
+    fn test_calculate_wallet_balance() {
+        let transactions = vec![
+            Transaction {
+                transaction_type: TransactionType::Deposit,
+                wallet_address: "ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3".to_string(),
+                amount: 100,
+                mint: None,
+                timestamp: 0,
+                fee: None,
+                signature: String::new(),
+                status: TransactionStatus::Confirmed,
+                memo: None,
+                signature_bytes: None,
+                message_bytes: None,
+                confirmations: 0,
+            },
+            Transaction {
+                transaction_type: TransactionType::Withdrawal,
+                wallet_address: "ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3".to_string(),
+                amount: 50,
+                mint: None,
+                timestamp: 0,
+                fee: None,
+                signature: String::new(),
+                status: TransactionStatus::Confirmed,
+                memo: None,
+                signature_bytes: None,
+                message_bytes: None,
+                confirmations: 0,
+            },
+            Transaction {
+                transaction_type: TransactionType::Deposit,
+                wallet_address: "BOBqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3".to_string(),
+                amount: 200,
+                mint: None,
+                timestamp: 0,
+                fee: None,
+                signature: String::new(),
+                status: TransactionStatus::Confirmed,
+                memo: None,
+                signature_bytes: None,
+                message_bytes: None,
+                confirmations: 0,
+            },
+            Transaction {
+                transaction_type: TransactionType::Withdrawal,
+                wallet_address: "BOBqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3".to_string(),
+                amount: 75,
+                mint: None,
+                timestamp: 0,
+                fee: None,
+                signature: String::new(),
+                status: TransactionStatus::Confirmed,
+                memo: None,
+                signature_bytes: None,
+                message_bytes: None,
+                confirmations: 0,
+            },
+            Transaction {
+                transaction_type: TransactionType::Deposit,
+                wallet_address: "ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3".to_string(),
+                amount: 25,
+                mint: None,
+                timestamp: 0,
+                fee: None,
+                signature: String::new(),
+                status: TransactionStatus::Confirmed,
+                memo: None,
+                signature_bytes: None,
+                message_bytes: None,
+                confirmations: 0,
+            },
+        ];
+
+        let result = calculate_wallet_balance(&wallet("ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3"), &transactions);
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap(), 75);
+    }
+
+    /// Tests error handling for invalid wallet addresses
+    #[test]
+    fn test_invalid_wallet_address() {
+        let transactions = vec![];
+        assert!(matches!(
+            calculate_available_balance("", &transactions),
+            Err(TransactionError::InvalidWalletAddress(_))
+        ));
+    }
+
+    /// Tests error handling for empty transaction list
+    #[test]
+    fn test_empty_transaction_list() {
+        let transactions = vec![];
+        assert!(matches!(
+            calculate_wallet_balance(&wallet("ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3"), &transactions),
+            Err(TransactionError::NoTransactions(_))
+        ));
+    }
+
+    /// Tests error handling for zero amount transactions
+    #[test]
+    fn test_zero_amount() {
+        let transactions = vec![Transaction {
+            transaction_type: TransactionType::Deposit,
+            wallet_address: "ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3".to_string(),
+            amount: 0,
+            mint: None,
+            timestamp: 0,
+            fee: None,
+            signature: String::new(),
+            status: TransactionStatus::Confirmed,
+            memo: None,
+            signature_bytes: None,
+            message_bytes: None,
+            confirmations: 0,
+        }];
+        assert!(matches!(
+            calculate_wallet_balance(&wallet("ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3"), &transactions),
+            Err(TransactionError::InvalidTransaction { index: 0, .. })
+        ));
+    }
+
+    /// Tests that deposits exceeding i64::MAX return an overflow error instead of panicking
+    #[test]
+    fn test_balance_overflow() {
+        let transactions = vec![
+            Transaction {
+                transaction_type: TransactionType::Deposit,
+                wallet_address: "ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3".to_string(),
+                amount: i64::MAX,
+                mint: None,
+                timestamp: 0,
+                fee: None,
+                signature: String::new(),
+                status: TransactionStatus::Confirmed,
+                memo: None,
+                signature_bytes: None,
+                message_bytes: None,
+                confirmations: 0,
+            },
+            Transaction {
+                transaction_type: TransactionType::Deposit,
+                wallet_address: "ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3".to_string(),
+                amount: i64::MAX,
+                mint: None,
+                timestamp: 0,
+                fee: None,
+                signature: String::new(),
+                status: TransactionStatus::Confirmed,
+                memo: None,
+                signature_bytes: None,
+                message_bytes: None,
+                confirmations: 0,
+            },
+        ];
+        assert!(matches!(
+            calculate_wallet_balance(&wallet("ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3"), &transactions),
+            Err(TransactionError::BalanceOverflow(_))
+        ));
+    }
+
+    /// Tests that withdrawals below i64::MIN return an overflow error instead of panicking
+    #[test]
+    fn test_balance_underflow() {
+        let transactions = vec![
+            Transaction {
+                transaction_type: TransactionType::Withdrawal,
+                wallet_address: "ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3".to_string(),
+                amount: i64::MAX,
+                mint: None,
+                timestamp: 0,
+                fee: None,
+                signature: String::new(),
+                status: TransactionStatus::Confirmed,
+                memo: None,
+                signature_bytes: None,
+                message_bytes: None,
+                confirmations: 0,
+            },
+            Transaction {
+                transaction_type: TransactionType::Withdrawal,
+                wallet_address: "ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3".to_string(),
+                amount: i64::MAX,
+                mint: None,
+                timestamp: 0,
+                fee: None,
+                signature: String::new(),
+                status: TransactionStatus::Confirmed,
+                memo: None,
+                signature_bytes: None,
+                message_bytes: None,
+                confirmations: 0,
+            },
+        ];
+        assert!(matches!(
+            calculate_wallet_balance(&wallet("ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3"), &transactions),
+            Err(TransactionError::BalanceOverflow(_))
+        ));
+    }
+
+    /// Tests that the running balance may pass i64::MAX as long as the final balance fits
+    #[test]
+    fn test_intermediate_sum_exceeds_i64() {
+        let transactions = vec![
+            Transaction::new(TransactionType::Deposit, ALICE, i64::MAX).unwrap(),
+            Transaction::new(TransactionType::Deposit, ALICE, 10).unwrap(),
+            Transaction::new(TransactionType::Withdrawal, ALICE, i64::MAX).unwrap(),
+        ];
+        assert_eq!(calculate_wallet_balance(&wallet(ALICE), &transactions).unwrap(), 10);
+    }
+
+    /// Tests that a withdrawal whose amount plus fee exceeds i64::MAX is accumulated exactly
+    #[test]
+    fn test_delta_exceeds_i64() {
+        let transactions = vec![
+            Transaction::new(TransactionType::Deposit, ALICE, i64::MAX).unwrap(),
+            Transaction::new(TransactionType::Withdrawal, ALICE, i64::MAX).unwrap().with_fee(10).unwrap(),
+        ];
+        assert_eq!(calculate_wallet_balance(&wallet(ALICE), &transactions).unwrap(), -10);
+    }
+
+    /// Tests error handling for a deposit with a negative amount
+    #[test]
+    fn test_negative_deposit() {
+        let transactions = vec![Transaction {
+            transaction_type: TransactionType::Deposit,
+            wallet_address: "ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3".to_string(),
+            amount: -100,
+            mint: None,
+            timestamp: 0,
+            fee: None,
+            signature: String::new(),
+            status: TransactionStatus::Confirmed,
+            memo: None,
+            signature_bytes: None,
+            message_bytes: None,
+            confirmations: 0,
+        }];
+        assert!(matches!(
+            calculate_wallet_balance(&wallet("ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3"), &transactions),
+            Err(TransactionError::InvalidTransaction { index: 0, source }) if source.to_string() == "Amount cannot be negative: -100"
+        ));
+    }
+
+    /// Tests error handling for a withdrawal with a negative amount
+    #[test]
+    fn test_negative_withdrawal() {
+        let transactions = vec![Transaction {
+            transaction_type: TransactionType::Withdrawal,
+            wallet_address: "ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3".to_string(),
+            amount: -50,
+            mint: None,
+            timestamp: 0,
+            fee: None,
+            signature: String::new(),
+            status: TransactionStatus::Confirmed,
+            memo: None,
+            signature_bytes: None,
+            message_bytes: None,
+            confirmations: 0,
+        }];
+        assert!(matches!(
+            calculate_wallet_balance(&wallet("ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3"), &transactions),
+            Err(TransactionError::InvalidTransaction { index: 0, source }) if source.to_string() == "Amount cannot be negative: -50"
+        ));
+    }
+
+    /// Tests computing balances for several interleaved wallets in one pass
+    #[test]
+    fn test_calculate_all_balances() {
+        let transactions = vec![
+            Transaction::new(TransactionType::Deposit, ALICE, 100).unwrap(),
+            Transaction::new(TransactionType::Deposit, BOB, 200).unwrap(),
+            Transaction::new(TransactionType::Deposit, CAROL, 300).unwrap(),
+            Transaction::new(TransactionType::Withdrawal, ALICE, 40).unwrap(),
+            Transaction::new(TransactionType::Withdrawal, CAROL, 150).unwrap(),
+            Transaction::new(TransactionType::Withdrawal, BOB, 25).unwrap(),
+        ];
+
+        let balances = calculate_all_balances(&transactions).unwrap();
+        assert_eq!(balances.len(), 3);
+        assert_eq!(balances[&(ALICE.to_string(), None)], 60);
+        assert_eq!(balances[&(BOB.to_string(), None)], 175);
+        assert_eq!(balances[&(CAROL.to_string(), None)], 150);
+    }
+
+    /// Tests that computing all balances rejects zero amounts
+    #[test]
+    fn test_calculate_all_balances_zero_amount() {
+        let transactions = vec![Transaction {
+            transaction_type: TransactionType::Deposit,
+            wallet_address: ALICE.to_string(),
+            amount: 0,
+            mint: None,
+            timestamp: 0,
+            fee: None,
+            signature: String::new(),
+            status: TransactionStatus::Confirmed,
+            memo: None,
+            signature_bytes: None,
+            message_bytes: None,
+            confirmations: 0,
+        }];
+        assert!(matches!(
+            calculate_all_balances(&transactions),
+            Err(TransactionError::InvalidTransaction { index: 0, .. })
+        ));
+    }
+
+    /// Tests that native SOL and different SPL mints are tracked separately for one wallet
+    #[test]
+    fn test_calculate_all_balances_by_mint() {
+        let transactions = vec![
+            Transaction::new(TransactionType::Deposit, ALICE, 1_000).unwrap(),
+            Transaction::new(TransactionType::Deposit, ALICE, 500).unwrap().with_mint(USDC).unwrap(),
+            Transaction::new(TransactionType::Deposit, ALICE, 70).unwrap().with_mint(BONK).unwrap(),
+            Transaction::new(TransactionType::Withdrawal, ALICE, 200).unwrap().with_mint(USDC).unwrap(),
+            Transaction::new(TransactionType::Withdrawal, ALICE, 100).unwrap(),
+        ];
+
+        let balances = calculate_all_balances(&transactions).unwrap();
+        assert_eq!(balances.len(), 3);
+        assert_eq!(balances[&(ALICE.to_string(), None)], 900);
+        assert_eq!(balances[&(ALICE.to_string(), Some(USDC.to_string()))], 300);
+        assert_eq!(balances[&(ALICE.to_string(), Some(BONK.to_string()))], 70);
+    }
+
+    /// Tests that SPL token transactions do not affect the native balance
+    #[test]
+    fn test_calculate_wallet_balance_ignores_tokens() {
+        let transactions = vec![
+            Transaction::new(TransactionType::Deposit, ALICE, 1_000).unwrap(),
+            Transaction::new(TransactionType::Deposit, ALICE, 500).unwrap().with_mint(USDC).unwrap(),
+        ];
+        assert_eq!(calculate_wallet_balance(&wallet(ALICE), &transactions).unwrap(), 1_000);
+    }
+
+    /// Tests that an overdrawing withdrawal is rejected when negative balances are disallowed
+    #[test]
+    fn test_checked_balance_rejects_overdraft() {
+        let transactions = vec![
+            Transaction::new(TransactionType::Deposit, ALICE, 100).unwrap(),
+            Transaction::new(TransactionType::Withdrawal, ALICE, 150).unwrap(),
+            Transaction::new(TransactionType::Deposit, ALICE, 500).unwrap(),
+        ];
+        assert!(matches!(
+            calculate_wallet_balance_checked(ALICE, &transactions, false),
+            Err(TransactionError::InsufficientFunds { wallet, balance: 100, attempted: 150 }) if wallet == ALICE
+        ));
+    }
+
+    /// Tests that a withdrawal overdrawn only by its fee reports the whole debit as attempted
+    #[test]
+    fn test_checked_balance_rejects_fee_overdraft() {
+        let transactions = vec![
+            Transaction::new(TransactionType::Deposit, ALICE, 100).unwrap(),
+            Transaction::new(TransactionType::Withdrawal, ALICE, 90).unwrap().with_fee(20).unwrap(),
+        ];
+        assert!(matches!(
+            calculate_wallet_balance_checked(ALICE, &transactions, false),
+            Err(TransactionError::InsufficientFunds { balance: 100, attempted: 110, .. })
+        ));
+    }
+
+    /// Tests that an overdrawing withdrawal is permitted when negative balances are allowed
+    #[test]
+    fn test_checked_balance_permits_overdraft() {
+        let transactions = vec![
+            Transaction::new(TransactionType::Deposit, ALICE, 100).unwrap(),
+            Transaction::new(TransactionType::Withdrawal, ALICE, 150).unwrap(),
+        ];
+        assert_eq!(calculate_wallet_balance_checked(ALICE, &transactions, true).unwrap(), -50);
+        assert_eq!(calculate_wallet_balance(&wallet(ALICE), &transactions).unwrap(), -50);
+    }
+
+    /// Tests that a transfer lowers the sender's balance and raises the receiver's by the same amount
+    #[test]
+    fn test_transfer_between_wallets() {
+        let transactions = vec![
+            Transaction::new(TransactionType::Deposit, ALICE, 500).unwrap(),
+            Transaction::new(TransactionType::Deposit, BOB, 100).unwrap(),
+            Transaction::new(TransactionType::Transfer { to: BOB.to_string() }, ALICE, 200).unwrap(),
+        ];
+
+        let balances = calculate_all_balances(&transactions).unwrap();
+        assert_eq!(balances[&(ALICE.to_string(), None)], 300);
+        assert_eq!(balances[&(BOB.to_string(), None)], 300);
+        assert_eq!(calculate_wallet_balance(&wallet(ALICE), &transactions).unwrap(), 300);
+        assert_eq!(calculate_wallet_balance(&wallet(BOB), &transactions).unwrap(), 300);
+    }
+
+    /// Tests that a transfer to the sending wallet is flagged rather than treated as a no-op
+    #[test]
+    fn test_self_transfer() {
+        assert!(matches!(
+            Transaction::new(TransactionType::Transfer { to: ALICE.to_string() }, ALICE, 10),
+            Err(TransactionError::SelfTransfer(to)) if to == ALICE
+        ));
+
+        let mut tx = Transaction::new(TransactionType::Transfer { to: BOB.to_string() }, ALICE, 10).unwrap();
+        tx.transaction_type = TransactionType::Transfer { to: ALICE.to_string() };
+        let transactions = vec![Transaction::new(TransactionType::Deposit, ALICE, 100).unwrap(), tx];
+        assert!(matches!(
+            calculate_wallet_balance(&wallet(ALICE), &transactions),
+            Err(TransactionError::InvalidTransaction { index: 1, source })
+                if matches!(&*source, TransactionError::SelfTransfer(to) if to == ALICE)
+        ));
+    }
+
+    /// Tests that an opening balance seeds the wallet's balance before normal activity
+    #[test]
+    fn test_opening_balance() {
+        let transactions = vec![
+            Transaction::new(TransactionType::OpeningBalance, ALICE, 1_000).unwrap(),
+            Transaction::new(TransactionType::Deposit, BOB, 50).unwrap(),
+            Transaction::new(TransactionType::Withdrawal, ALICE, 300).unwrap(),
+            Transaction::new(TransactionType::Transfer { to: ALICE.to_string() }, BOB, 20).unwrap(),
+        ];
+
+        assert_eq!(calculate_wallet_balance(&wallet(ALICE), &transactions).unwrap(), 720);
+        assert_eq!(calculate_all_balances(&transactions).unwrap()[&(ALICE.to_string(), None)], 720);
+    }
+
+    /// Tests that an opening balance after other activity for the wallet is rejected
+    #[test]
+    fn test_misplaced_opening_balance() {
+        let transactions = vec![
+            Transaction::new(TransactionType::Deposit, ALICE, 100).unwrap(),
+            Transaction::new(TransactionType::OpeningBalance, ALICE, 1_000).unwrap(),
+        ];
+
+        assert!(matches!(
+            calculate_wallet_balance(&wallet(ALICE), &transactions),
+            Err(TransactionError::InvalidTransaction { index: 1, source })
+                if matches!(&*source, TransactionError::MisplacedOpeningBalance(wallet) if wallet == ALICE)
+        ));
+        assert!(matches!(
+            calculate_all_balances(&transactions),
+            Err(TransactionError::InvalidTransaction { index: 1, source })
+                if matches!(*source, TransactionError::MisplacedOpeningBalance(_))
+        ));
+    }
+
+    /// Tests that an SPL token transaction does not stop a native opening balance from following it
+    #[test]
+    fn test_opening_balance_after_spl_transaction() {
+        let transactions = vec![
+            Transaction::new(TransactionType::Deposit, ALICE, 500).unwrap().with_mint(USDC).unwrap(),
+            Transaction::new(TransactionType::OpeningBalance, ALICE, 1_000).unwrap(),
+            Transaction::new(TransactionType::Withdrawal, ALICE, 100).unwrap(),
+        ];
+
+        assert_eq!(calculate_wallet_balance(&wallet(ALICE), &transactions).unwrap(), 900);
+        assert_eq!(calculate_all_balances(&transactions).unwrap()[&(ALICE.to_string(), None)], 900);
+    }
+
+    /// Tests that a deposit's fee is deducted from the depositing wallet
+    #[test]
+    fn test_deposit_with_fee() {
+        let transactions = vec![Transaction::new(TransactionType::Deposit, ALICE, 100)
+            .unwrap()
+            .with_fee(5)
+            .unwrap()];
+        assert_eq!(calculate_wallet_balance(&wallet(ALICE), &transactions).unwrap(), 95);
+    }
+
+    /// Tests that a transfer's fee is paid by the sender only
+    #[test]
+    fn test_transfer_fee_paid_by_sender() {
+        let transactions = vec![
+            Transaction::new(TransactionType::Deposit, ALICE, 100).unwrap(),
+            Transaction::new(TransactionType::Transfer { to: BOB.to_string() }, ALICE, 50)
+                .unwrap()
+                .with_fee(5)
+                .unwrap(),
+        ];
+        assert_eq!(calculate_wallet_balance(&wallet(ALICE), &transactions).unwrap(), 45);
+        assert_eq!(calculate_wallet_balance(&wallet(BOB), &transactions).unwrap(), 50);
+    }
+
+    /// Tests that the gross balance exceeds the net balance by the fees paid
+    #[test]
+    fn test_gross_balance() {
+        let transactions = vec![
+            Transaction::new(TransactionType::Deposit, ALICE, 1_000).unwrap().with_fee(5).unwrap(),
+            Transaction::new(TransactionType::Transfer { to: BOB.to_string() }, ALICE, 200)
+                .unwrap()
+                .with_fee(10)
+                .unwrap(),
+            Transaction::new(TransactionType::Fee, ALICE, 3).unwrap(),
+            Transaction::new(TransactionType::Transfer { to: ALICE.to_string() }, BOB, 50)
+                .unwrap()
+                .with_fee(7)
+                .unwrap(),
+        ];
+
+        let gross = calculate_wallet_balance_gross(ALICE, &transactions).unwrap();
+        let net = calculate_wallet_balance(&wallet(ALICE), &transactions).unwrap();
+        assert_eq!(gross, 850);
+        assert_eq!(gross - net, 5 + 10 + 3);
+    }
+
+    /// Tests that a negative fee is rejected while calculating the balance
+    #[test]
+    fn test_negative_fee() {
+        let mut tx = Transaction::new(TransactionType::Deposit, ALICE, 100).unwrap();
+        tx.fee = Some(-1);
+        assert!(matches!(
+            calculate_wallet_balance(&wallet(ALICE), &[tx]),
+            Err(TransactionError::InvalidTransaction { index: 0, source }) if source.to_string() == "Fee cannot be negative: -1"
+        ));
+    }
+
+    /// Iterator that hands out transactions one at a time, like a streaming reader would
+    struct OneAtATime {
+        remaining: Vec<Transaction>,
+    }
+
+    impl Iterator for OneAtATime {
+        type Item = Transaction;
+
+        fn next(&mut self) -> Option<Transaction> {
+            if self.remaining.is_empty() {
+                None
+            } else {
+                Some(self.remaining.remove(0))
+            }
+        }
+    }
+
+    /// Tests calculating a balance lazily from a custom iterator
+    #[test]
+    fn test_calculate_balance_from_iter() {
+        let stream = OneAtATime {
+            remaining: vec![
+                Transaction::new(TransactionType::Deposit, ALICE, 100).unwrap(),
+                Transaction::new(TransactionType::Deposit, BOB, 70).unwrap(),
+                Transaction::new(TransactionType::Withdrawal, ALICE, 35).unwrap(),
+            ],
+        };
+        assert_eq!(calculate_balance_from_iter(ALICE, stream).unwrap(), 65);
+    }
+
+    /// Tests error handling for an empty transaction stream
+    #[test]
+    fn test_calculate_balance_from_empty_iter() {
+        assert!(matches!(
+            calculate_balance_from_iter(ALICE, std::iter::empty()),
+            Err(TransactionError::NoTransactions(_))
+        ));
+    }
+
+    /// Tests that strict mode reports a malformed address belonging to another wallet
+    #[test]
+    fn test_strict_balance_rejects_malformed_address() {
+        let mut malformed = Transaction::new(TransactionType::Deposit, BOB, 10).unwrap();
+        malformed.wallet_address = "not-a-wallet".to_string();
+        let transactions = vec![
+            Transaction::new(TransactionType::Deposit, ALICE, 100).unwrap(),
+            malformed,
+            Transaction::new(TransactionType::Withdrawal, ALICE, 25).unwrap(),
+        ];
+
+        assert_eq!(calculate_wallet_balance(&wallet(ALICE), &transactions).unwrap(), 75);
+        assert!(matches!(
+            calculate_wallet_balance_strict(ALICE, &transactions),
+            Err(TransactionError::InvalidTransaction { index: 1, source })
+                if matches!(&*source, TransactionError::InvalidWalletAddress(address) if address == "not-a-wallet")
+        ));
+    }
+
+    /// Tests a withdrawal that leaves exactly the minimum balance
+    #[test]
+    fn test_floor_at_minimum() {
+        let transactions = vec![
+            Transaction::new(TransactionType::Deposit, ALICE, 1_000).unwrap(),
+            Transaction::new(TransactionType::Withdrawal, ALICE, 110).unwrap(),
+        ];
+        assert_eq!(calculate_wallet_balance_with_floor(ALICE, &transactions, 890).unwrap(), 890);
+    }
+
+    /// Tests a withdrawal that leaves just below the minimum balance
+    #[test]
+    fn test_floor_just_below_minimum() {
+        let transactions = vec![
+            Transaction::new(TransactionType::Deposit, ALICE, 1_000).unwrap(),
+            Transaction::new(TransactionType::Withdrawal, ALICE, 111).unwrap(),
+            Transaction::new(TransactionType::Deposit, ALICE, 1_000).unwrap(),
+        ];
+        assert!(matches!(
+            calculate_wallet_balance_with_floor(ALICE, &transactions, 890),
+            Err(TransactionError::BelowRentExempt { wallet, balance: 889, min: 890 }) if wallet == ALICE
+        ));
+    }
+
+    /// Tests a withdrawal that leaves more than the minimum balance
+    #[test]
+    fn test_floor_above_minimum() {
+        let transactions = vec![
+            Transaction::new(TransactionType::Deposit, ALICE, 1_000).unwrap(),
+            Transaction::new(TransactionType::Withdrawal, ALICE, 109).unwrap(),
+        ];
+        assert_eq!(calculate_wallet_balance_with_floor(ALICE, &transactions, 890).unwrap(), 891);
+    }
+
+    /// Tests that a duplicated signature is only counted once
+    #[test]
+    fn test_dedup_balance() {
+        let deposit = Transaction::new(TransactionType::Deposit, ALICE, 100).unwrap().with_signature("5VERv8NMvzbJMEkV");
+        let transactions = vec![
+            deposit.clone(),
+            Transaction::new(TransactionType::Withdrawal, ALICE, 30).unwrap(),
+            deposit,
+        ];
+
+        assert_eq!(calculate_wallet_balance(&wallet(ALICE), &transactions).unwrap(), 170);
+        assert_eq!(calculate_wallet_balance_dedup(ALICE, &transactions).unwrap(), 70);
+    }
+
+    /// Tests how each status affects the confirmed and available balances
+    #[test]
+    fn test_confirmed_and_available_balances() {
+        let transactions = vec![
+            Transaction::new(TransactionType::Deposit, ALICE, 100).unwrap(),
+            Transaction::new(TransactionType::Deposit, ALICE, 40)
+                .unwrap()
+                .with_status(TransactionStatus::Pending),
+            Transaction::new(TransactionType::Withdrawal, ALICE, 500)
+                .unwrap()
+                .with_status(TransactionStatus::Failed),
+            Transaction::new(TransactionType::Withdrawal, ALICE, 10)
+                .unwrap()
+                .with_status(TransactionStatus::Confirmed),
+        ];
+
+        assert_eq!(calculate_confirmed_balance(ALICE, &transactions).unwrap(), 90);
+        assert_eq!(calculate_available_balance(ALICE, &transactions).unwrap(), 130);
+        assert_eq!(calculate_wallet_balance(&wallet(ALICE), &transactions).unwrap(), 130);
+    }
+
+    /// Tests that lowering the confirmation threshold counts more recent transactions
+    #[test]
+    fn test_balance_min_confirmations() {
+        let transactions = vec![
+            Transaction::new(TransactionType::Deposit, ALICE, 100).unwrap().with_confirmations(40),
+            Transaction::new(TransactionType::Withdrawal, ALICE, 30).unwrap().with_confirmations(32),
+            Transaction::new(TransactionType::Deposit, ALICE, 5).unwrap().with_confirmations(1),
+        ];
+
+        assert_eq!(calculate_balance_min_confirmations(ALICE, &transactions, 32).unwrap(), 70);
+        assert_eq!(calculate_balance_min_confirmations(ALICE, &transactions, 1).unwrap(), 75);
+        assert_eq!(calculate_balance_min_confirmations(ALICE, &transactions, 41).unwrap(), 0);
+    }
+
+    /// Tests that the queried address is trimmed before matching transactions
+    #[test]
+    fn test_balance_with_padded_address() {
+        let transactions = vec![Transaction::new(TransactionType::Deposit, ALICE, 100).unwrap()];
+        assert_eq!(calculate_wallet_balance(&wallet(&format!(" {} ", ALICE)), &transactions).unwrap(), 100);
+    }
+
+    /// Tests that native balances leave out SPL token holdings
+    #[test]
+    fn test_calculate_native_balances() {
+        let transactions = vec![
+            Transaction::new(TransactionType::Deposit, ALICE, 100).unwrap(),
+            Transaction::new(TransactionType::Deposit, BOB, 50).unwrap().with_mint(USDC).unwrap(),
+        ];
+
+        let balances = calculate_native_balances(&transactions).unwrap();
+        assert_eq!(balances, HashMap::from([(ALICE.to_string(), 100)]));
+    }
+
+    /// Tests that dust is left out of the balance only when excluded
+    #[test]
+    fn test_balance_without_dust() {
+        let transactions = vec![
+            Transaction::new(TransactionType::Deposit, ALICE, 1_000).unwrap(),
+            Transaction::new(TransactionType::Deposit, ALICE, 2).unwrap().with_fee(1).unwrap(),
+            Transaction::new(TransactionType::Withdrawal, ALICE, 300).unwrap(),
+            Transaction::new(TransactionType::Withdrawal, ALICE, 4).unwrap(),
+        ];
+
+        assert_eq!(calculate_wallet_balance(&wallet(ALICE), &transactions).unwrap(), 697);
+        assert_eq!(calculate_wallet_balance_without_dust(ALICE, &transactions, 5).unwrap(), 700);
+        assert_eq!(calculate_wallet_balance_without_dust(ALICE, &transactions, 2_000).unwrap(), 0);
+    }
+
+    /// Tests that doubling fees lowers the balance by the fees paid, and waiving them restores the gross
+    #[test]
+    fn test_project_with_fee_multiplier() {
+        let transactions = vec![
+            Transaction::new(TransactionType::Deposit, ALICE, 10_000).unwrap(),
+            Transaction::new(TransactionType::Withdrawal, ALICE, 1_000).unwrap().with_fee(5_000).unwrap(),
+            Transaction::new(TransactionType::Fee, ALICE, 500).unwrap(),
+        ];
+
+        let current = calculate_wallet_balance(&wallet(ALICE), &transactions).unwrap();
+        assert_eq!(current, 3_500);
+        assert_eq!(project_with_fee_multiplier(ALICE, &transactions, 1.0).unwrap(), current);
+        assert_eq!(project_with_fee_multiplier(ALICE, &transactions, 2.0).unwrap(), -2_000);
+        assert_eq!(project_with_fee_multiplier(ALICE, &transactions, 0.0).unwrap(), 9_000);
+        assert!(matches!(
+            project_with_fee_multiplier(ALICE, &transactions, -0.5),
+            Err(TransactionError::InvalidFeeMultiplier(m)) if m == -0.5
+        ));
+    }
+
+    /// Tests that an invalid address part-way through the history is reported with its index
+    #[test]
+    fn test_calculate_all_balances_locates_invalid_address() {
+        let mut malformed = Transaction::new(TransactionType::Transfer { to: BOB.to_string() }, ALICE, 10).unwrap();
+        malformed.transaction_type = TransactionType::Transfer { to: "not-a-wallet".to_string() };
+        let transactions = vec![
+            Transaction::new(TransactionType::Deposit, ALICE, 100).unwrap(),
+            Transaction::new(TransactionType::Deposit, BOB, 50).unwrap(),
+            malformed,
+        ];
+
+        let error = calculate_all_balances(&transactions).unwrap_err();
+        assert!(matches!(
+            &error,
+            TransactionError::InvalidTransaction { index: 2, source }
+                if matches!(&**source, TransactionError::InvalidWalletAddress(address) if address == "not-a-wallet")
+        ));
+        assert!(std::error::Error::source(&error).is_some());
+    }
+
+    /// Tests that balances are returned in address order regardless of input order
+    #[test]
+    fn test_balances_sorted() {
+        let transactions = vec![
+            Transaction::new(TransactionType::Deposit, CAROL, 300).unwrap(),
+            Transaction::new(TransactionType::Deposit, ALICE, 100).unwrap(),
+            Transaction::new(TransactionType::Deposit, BOB, 200).unwrap(),
+        ];
+
+        let balances = balances_sorted(&transactions).unwrap();
+        // Base58 sorts digits before letters, so CAROL ("68…") comes first
+        assert_eq!(balances, vec![(CAROL.to_string(), 300), (BOB.to_string(), 200), (ALICE.to_string(), 100)]);
+    }
+
+    /// Tests that a standalone fee transaction reduces the balance alongside deposits and withdrawals
+    #[test]
+    fn test_fee_transaction() {
+        let transactions = vec![
+            Transaction::new(TransactionType::Deposit, ALICE, 1_000).unwrap(),
+            Transaction::new(TransactionType::Fee, ALICE, 5_000).unwrap(),
+            Transaction::new(TransactionType::Withdrawal, ALICE, 200).unwrap(),
+            Transaction::new(TransactionType::Fee, ALICE, 5).unwrap(),
+            Transaction::new(TransactionType::Deposit, ALICE, 10_000).unwrap(),
+        ];
+        assert_eq!(calculate_wallet_balance(&wallet(ALICE), &transactions).unwrap(), 5_795);
+    }
+
+    /// Tests a transaction exactly at the amount limit
+    #[test]
+    fn test_limit_at_maximum() {
+        let transactions = vec![Transaction::new(TransactionType::Deposit, ALICE, 1_000).unwrap()];
+        assert_eq!(calculate_wallet_balance_with_limit(ALICE, &transactions, 1_000).unwrap(), 1_000);
+    }
+
+    /// Tests a transaction just over the amount limit
+    #[test]
+    fn test_limit_just_over_maximum() {
+        let transactions = vec![
+            Transaction::new(TransactionType::Deposit, ALICE, 500).unwrap(),
+            Transaction::new(TransactionType::Withdrawal, ALICE, 1_001).unwrap(),
+        ];
+        assert!(matches!(
+            calculate_wallet_balance_with_limit(ALICE, &transactions, 1_000),
+            Err(TransactionError::AmountExceedsLimit { amount: 1_001, max: 1_000 })
+        ));
+    }
+
+    /// Tests a transaction just under the amount limit
+    #[test]
+    fn test_limit_just_under_maximum() {
+        let transactions = vec![Transaction::new(TransactionType::Deposit, ALICE, 999).unwrap()];
+        assert_eq!(calculate_wallet_balance_with_limit(ALICE, &transactions, 1_000).unwrap(), 999);
+    }
+
+    /// Tests that only transactions involving the wallet are yielded, including incoming transfers
+    #[test]
+    fn test_transactions_for_wallet() {
+        let transactions = vec![
+            Transaction::new(TransactionType::Deposit, ALICE, 1).unwrap(),
+            Transaction::new(TransactionType::Deposit, BOB, 2).unwrap(),
+            Transaction::new(TransactionType::Transfer { to: ALICE.to_string() }, BOB, 3).unwrap(),
+            Transaction::new(TransactionType::Withdrawal, BOB, 4).unwrap(),
+            Transaction::new(TransactionType::Withdrawal, ALICE, 5).unwrap(),
+        ];
+
+        let amounts: Vec<i64> = transactions_for_wallet(ALICE, &transactions).map(Transaction::amount).collect();
+        assert_eq!(amounts, vec![1, 3, 5]);
+    }
+
+    /// Tests that a wallet absent from a populated history is reported as having no transactions
+    #[test]
+    fn test_wallet_absent_from_history() {
+        let transactions = vec![
+            Transaction::new(TransactionType::Deposit, BOB, 100).unwrap(),
+            Transaction::new(TransactionType::Transfer { to: CAROL.to_string() }, BOB, 40).unwrap(),
+        ];
+
+        assert!(matches!(
+            calculate_wallet_balance(&wallet(ALICE), &transactions),
+            Err(TransactionError::NoTransactions(wallet)) if wallet == ALICE
+        ));
+        assert_eq!(calculate_wallet_balance(&wallet(CAROL), &transactions).unwrap(), 40);
+    }
+
+    /// Tests distinct scheduled deposit and withdrawal fees against the net balance
+    #[test]
+    fn test_balance_with_fee_schedule() {
+        let schedule = FeeSchedule::new()
+            .with_fee(&TransactionType::Deposit, 1)
+            .unwrap()
+            .with_fee(&TransactionType::Withdrawal, 5)
+            .unwrap();
+        let transactions = vec![
+            Transaction::new(TransactionType::Deposit, ALICE, 100).unwrap(),
+            Transaction::new(TransactionType::Deposit, ALICE, 50).unwrap(),
+            Transaction::new(TransactionType::Withdrawal, ALICE, 30).unwrap(),
+            Transaction::new(TransactionType::Transfer { to: ALICE.to_string() }, BOB, 20).unwrap(),
+        ];
+
+        // 100 + 50 - 30 + 20, less two deposit fees and one withdrawal fee; transfers are free
+        assert_eq!(
+            calculate_wallet_balance_with_schedule(ALICE, &transactions, &schedule).unwrap(),
+            133
+        );
+    }
+
+    /// Tests that skipping a transaction for another wallet emits a warning
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn test_warns_on_skipped_transaction() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+        use tracing::span::{Attributes, Id, Record};
+        use tracing::{Event, Level, Metadata, Subscriber};
+
+        /// Counts the warning events it receives
+        struct WarningCounter(Arc<AtomicUsize>);
+
+        impl Subscriber for WarningCounter {
+            fn enabled(&self, _: &Metadata<'_>) -> bool {
+                true
+            }
+            fn new_span(&self, _: &Attributes<'_>) -> Id {
+                Id::from_u64(1)
+            }
+            fn record(&self, _: &Id, _: &Record<'_>) {}
+            fn record_follows_from(&self, _: &Id, _: &Id) {}
+            fn event(&self, event: &Event<'_>) {
+                if *event.metadata().level() == Level::WARN {
+                    self.0.fetch_add(1, Ordering::SeqCst);
+                }
+            }
+            fn enter(&self, _: &Id) {}
+            fn exit(&self, _: &Id) {}
+        }
+
+        let warnings = Arc::new(AtomicUsize::new(0));
+        let transactions = vec![
+            Transaction::new(TransactionType::Deposit, ALICE, 100).unwrap(),
+            Transaction::new(TransactionType::Deposit, BOB, 50).unwrap(),
+        ];
+
+        let balance = tracing::subscriber::with_default(WarningCounter(Arc::clone(&warnings)), || {
+            calculate_wallet_balance(&wallet(ALICE), &transactions)
+        });
+        assert_eq!(balance.unwrap(), 100);
+        assert_eq!(warnings.load(Ordering::SeqCst), 1);
+    }
+
+    /// Tests that an invalid transaction is reported by its index in the input
+    #[test]
+    fn test_invalid_transaction_index() {
+        let mut zero = Transaction::new(TransactionType::Deposit, ALICE, 1).unwrap();
+        zero.amount = 0;
+        let transactions = vec![
+            Transaction::new(TransactionType::Deposit, ALICE, 100).unwrap(),
+            Transaction::new(TransactionType::Deposit, BOB, 50).unwrap(),
+            zero,
+        ];
+
+        assert!(matches!(
+            calculate_wallet_balance(&wallet(ALICE), &transactions),
+            Err(TransactionError::InvalidTransaction { index: 2, source }) if source.to_string() == "Amount cannot be zero"
+        ));
+    }
+
+    /// Tests the combined balance of three wallets, one listed twice
+    #[test]
+    fn test_aggregate_balance() {
+        let transactions = vec![
+            Transaction::new(TransactionType::Deposit, ALICE, 100).unwrap(),
+            Transaction::new(TransactionType::Deposit, BOB, 200).unwrap(),
+            Transaction::new(TransactionType::Deposit, CAROL, 300).unwrap(),
+            Transaction::new(TransactionType::Transfer { to: BOB.to_string() }, ALICE, 40)
+                .unwrap()
+                .with_fee(1)
+                .unwrap(),
+            Transaction::new(TransactionType::Transfer { to: ALICE.to_string() }, CAROL, 60).unwrap(),
+            Transaction::new(TransactionType::Withdrawal, CAROL, 10).unwrap(),
+        ];
+
+        // Alice 100 - 40 - 1 + 60 = 119, Bob 200 + 40 = 240
+        assert_eq!(aggregate_balance(&[ALICE, BOB, ALICE], &transactions).unwrap(), 359);
+        assert_eq!(aggregate_balance(&[ALICE, BOB, CAROL], &transactions).unwrap(), 589);
+    }
+
+    /// Tests that an opening balance after other activity for a listed wallet is rejected
+    #[test]
+    fn test_aggregate_balance_misplaced_opening_balance() {
+        let transactions = vec![
+            Transaction::new(TransactionType::Deposit, ALICE, 100).unwrap(),
+            Transaction::new(TransactionType::Deposit, BOB, 200).unwrap(),
+            Transaction::new(TransactionType::OpeningBalance, ALICE, 1_000).unwrap(),
+        ];
+
+        assert!(matches!(
+            aggregate_balance(&[ALICE, BOB], &transactions),
+            Err(TransactionError::InvalidTransaction { index: 2, source })
+                if matches!(*source, TransactionError::MisplacedOpeningBalance(_))
+        ));
+    }
+
+    /// Tests simulating a withdrawal that leaves a positive balance, without changing the history
+    #[test]
+    fn test_simulate_withdrawal() {
+        let transactions = vec![
+            Transaction::new(TransactionType::Deposit, ALICE, 100).unwrap(),
+            Transaction::new(TransactionType::Withdrawal, ALICE, 30).unwrap(),
+        ];
+        let proposed = Transaction::new(TransactionType::Withdrawal, ALICE, 50).unwrap();
+
+        assert_eq!(simulate(ALICE, &transactions, &proposed).unwrap(), 20);
+        assert_eq!(calculate_wallet_balance(&wallet(ALICE), &transactions).unwrap(), 70);
+    }
+
+    /// Tests that a simulated withdrawal overdrawing the wallet is rejected
+    #[test]
+    fn test_simulate_overdraft() {
+        let transactions = vec![Transaction::new(TransactionType::Deposit, ALICE, 100).unwrap()];
+        let proposed = Transaction::new(TransactionType::Withdrawal, ALICE, 101).unwrap();
+
+        assert!(matches!(
+            simulate(ALICE, &transactions, &proposed),
+            Err(TransactionError::InsufficientFunds { balance: 100, attempted: 101, .. })
+        ));
+    }
+
+    /// Tests that an overdrawing simulated withdrawal reports its fee as part of the attempted debit
+    #[test]
+    fn test_simulate_overdraft_with_fee() {
+        let transactions = vec![Transaction::new(TransactionType::Deposit, ALICE, 100).unwrap()];
+        let proposed = Transaction::new(TransactionType::Withdrawal, ALICE, 100).unwrap().with_fee(5).unwrap();
+
+        assert!(matches!(
+            simulate(ALICE, &transactions, &proposed),
+            Err(TransactionError::InsufficientFunds { balance: 100, attempted: 105, .. })
+        ));
+    }
+
+    /// Tests that a simulated opening balance is rejected for a wallet with history, but not for a new one
+    #[test]
+    fn test_simulate_opening_balance() {
+        let transactions = vec![Transaction::new(TransactionType::Deposit, ALICE, 100).unwrap()];
+        let proposed = Transaction::new(TransactionType::OpeningBalance, ALICE, 1_000).unwrap();
+
+        assert!(matches!(
+            simulate(ALICE, &transactions, &proposed),
+            Err(TransactionError::MisplacedOpeningBalance(wallet)) if wallet == ALICE
+        ));
+        assert_eq!(simulate(ALICE, &[], &proposed).unwrap(), 1_000);
+    }
+
+    /// Tests that simulating against a history with a misplaced opening balance is rejected
+    #[test]
+    fn test_simulate_misplaced_opening_balance() {
+        let transactions = vec![
+            Transaction::new(TransactionType::Deposit, ALICE, 100).unwrap(),
+            Transaction::new(TransactionType::OpeningBalance, ALICE, 1_000).unwrap(),
+        ];
+        let proposed = Transaction::new(TransactionType::Withdrawal, ALICE, 50).unwrap();
+
+        assert!(matches!(
+            simulate(ALICE, &transactions, &proposed),
+            Err(TransactionError::InvalidTransaction { index: 1, source })
+                if matches!(*source, TransactionError::MisplacedOpeningBalance(_))
+        ));
+    }
+
+    /// Tests calculating an Ethereum wallet's balance with a matching validator
+    #[test]
+    fn test_balance_with_validator() {
+        let eth_wallet = "0x52908400098527886E0F7030069857D2E4169EE7";
+        let mut deposit = Transaction::new(TransactionType::Deposit, ALICE, 100).unwrap();
+        deposit.wallet_address = eth_wallet.to_string();
+        let mut withdrawal = Transaction::new(TransactionType::Withdrawal, ALICE, 40).unwrap();
+        withdrawal.wallet_address = eth_wallet.to_string();
+        let transactions = vec![deposit, withdrawal];
+
+        assert_eq!(
+            calculate_wallet_balance_with_validator(eth_wallet, &transactions, &EthereumValidator).unwrap(),
+            60
+        );
+        assert!(matches!(
+            calculate_wallet_balance_with_validator(eth_wallet, &transactions, &SolanaValidator),
+            Err(TransactionError::InvalidWalletAddress(_))
+        ));
+    }
+
+    /// Tests that a frozen wallet's withdrawal is rejected while other wallets are unaffected
+    #[test]
+    fn test_frozen_wallet_withdrawal() {
+        let frozen = HashSet::from([ALICE.to_string()]);
+        let transactions = vec![
+            Transaction::new(TransactionType::Deposit, ALICE, 100).unwrap(),
+            Transaction::new(TransactionType::Deposit, BOB, 100).unwrap(),
+            Transaction::new(TransactionType::Withdrawal, ALICE, 30).unwrap(),
+            Transaction::new(TransactionType::Withdrawal, BOB, 30).unwrap(),
+        ];
+
+        assert!(matches!(
+            calculate_wallet_balance_with_freeze(ALICE, &transactions, &frozen),
+            Err(TransactionError::WalletFrozen(wallet)) if wallet == ALICE
+        ));
+        assert_eq!(calculate_wallet_balance_with_freeze(BOB, &transactions, &frozen).unwrap(), 70);
+
+        let transfer_out = vec![Transaction::new(TransactionType::Transfer { to: BOB.to_string() }, ALICE, 5).unwrap()];
+        assert!(matches!(
+            calculate_wallet_balance_with_freeze(ALICE, &transfer_out, &frozen),
+            Err(TransactionError::WalletFrozen(_))
+        ));
+    }
+
+    /// Tests that a frozen wallet still receives deposits and incoming transfers
+    #[test]
+    fn test_frozen_wallet_deposits_only() {
+        let frozen = HashSet::from([ALICE.to_string()]);
+        let transactions = vec![
+            Transaction::new(TransactionType::Deposit, ALICE, 100).unwrap(),
+            Transaction::new(TransactionType::Transfer { to: ALICE.to_string() }, BOB, 25).unwrap(),
+        ];
+        assert_eq!(calculate_wallet_balance_with_freeze(ALICE, &transactions, &frozen).unwrap(), 125);
+    }
+
+    /// Tests that a wallet with more transactions than the cap is rejected
+    #[test]
+    fn test_capped_balance() {
+        let transactions = vec![
+            Transaction::new(TransactionType::Deposit, ALICE, 100).unwrap(),
+            Transaction::new(TransactionType::Deposit, BOB, 100).unwrap(),
+            Transaction::new(TransactionType::Withdrawal, ALICE, 30).unwrap(),
+            Transaction::new(TransactionType::Deposit, ALICE, 5).unwrap(),
+        ];
+
+        assert_eq!(calculate_wallet_balance_capped(ALICE, &transactions, 3).unwrap(), 75);
+        assert!(matches!(
+            calculate_wallet_balance_capped(ALICE, &transactions, 2),
+            Err(TransactionError::TooManyTransactions { wallet, count: 3, max: 2 }) if wallet == ALICE
+        ));
+    }
+
+    /// Tests that rules loaded from a TOML file take effect
+    #[test]
+    fn test_balance_with_toml_config() {
+        let path = std::env::temp_dir().join("ryz_balance_with_toml_config.toml");
+        std::fs::write(&path, "min_amount = 10\nmax_amount = 1000\nallow_negative = false\n").unwrap();
+        let config = ValidationConfig::from_toml(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let valid = vec![
+            Transaction::new(TransactionType::Deposit, ALICE, 1_000).unwrap(),
+            Transaction::new(TransactionType::Withdrawal, ALICE, 10).unwrap(),
+        ];
+        assert_eq!(calculate_wallet_balance_with_config(ALICE, &valid, &config).unwrap(), 990);
+
+        let too_small = vec![Transaction::new(TransactionType::Deposit, ALICE, 9).unwrap()];
+        assert!(matches!(
+            calculate_wallet_balance_with_config(ALICE, &too_small, &config),
+            Err(TransactionError::AmountBelowMinimum { amount: 9, min: 10 })
+        ));
+
+        let too_large = vec![Transaction::new(TransactionType::Deposit, ALICE, 1_001).unwrap()];
+        assert!(matches!(
+            calculate_wallet_balance_with_config(ALICE, &too_large, &config),
+            Err(TransactionError::AmountExceedsLimit { amount: 1_001, max: 1_000 })
+        ));
+
+        let overdraft = vec![
+            Transaction::new(TransactionType::Deposit, ALICE, 50).unwrap(),
+            Transaction::new(TransactionType::Withdrawal, ALICE, 60).unwrap(),
+        ];
+        assert!(matches!(
+            calculate_wallet_balance_with_config(ALICE, &overdraft, &config),
+            Err(TransactionError::InsufficientFunds { balance: 50, attempted: 60, .. })
+        ));
+    }
+
+    /// Tests a 25% withdrawal from a known balance
+    #[test]
+    fn test_percentage_withdrawal() {
+        let transactions = vec![
+            Transaction::new(TransactionType::Deposit, ALICE, 1_000).unwrap(),
+            Transaction::new(TransactionType::Withdrawal, ALICE, 198).unwrap(),
+        ];
+
+        // 25% of 802 rounds down to 200
+        let withdrawal = percentage_withdrawal(ALICE, &transactions, 25).unwrap();
+        assert_eq!(withdrawal.transaction_type(), &TransactionType::Withdrawal);
+        assert_eq!(withdrawal.wallet_address(), ALICE);
+        assert_eq!(withdrawal.amount(), 200);
+    }
+
+    /// Tests that a percentage above 100 is rejected
+    #[test]
+    fn test_percentage_withdrawal_over_100() {
+        let transactions = vec![Transaction::new(TransactionType::Deposit, ALICE, 1_000).unwrap()];
+        assert!(matches!(
+            percentage_withdrawal(ALICE, &transactions, 101),
+            Err(TransactionError::InvalidPercentage(101))
+        ));
+    }
+}
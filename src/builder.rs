@@ -0,0 +1,189 @@
+use crate::error::TransactionError;
+use crate::transaction::{Transaction, TransactionStatus, TransactionType};
+
+/// Fluent builder for [`Transaction`], running every validation in [`build`](TransactionBuilder::build)
+///
+/// ```
+/// use ryz_coding_challenge::TransactionBuilder;
+///
+/// let tx = TransactionBuilder::new()
+///     .deposit(100)
+///     .wallet("ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3")
+///     .fee(5)
+///     .timestamp(1_700_000_000)
+///     .build()
+///     .unwrap();
+/// assert_eq!(tx.amount(), 100);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct TransactionBuilder {
+    transaction_type: Option<TransactionType>,
+    wallet_address: Option<String>,
+    amount: Option<i64>,
+    mint: Option<String>,
+    timestamp: i64,
+    fee: Option<i64>,
+    signature: String,
+    status: TransactionStatus,
+    memo: Option<String>,
+    signature_bytes: Option<[u8; 64]>,
+    message_bytes: Option<Vec<u8>>,
+    confirmations: u32,
+}
+
+impl TransactionBuilder {
+    /// Creates an empty builder
+    pub fn new() -> TransactionBuilder {
+        TransactionBuilder::default()
+    }
+
+    /// Makes the transaction a deposit of `amount`
+    pub fn deposit(mut self, amount: i64) -> TransactionBuilder {
+        self.transaction_type = Some(TransactionType::Deposit);
+        self.amount = Some(amount);
+        self
+    }
+
+    /// Makes the transaction a withdrawal of `amount`
+    pub fn withdrawal(mut self, amount: i64) -> TransactionBuilder {
+        self.transaction_type = Some(TransactionType::Withdrawal);
+        self.amount = Some(amount);
+        self
+    }
+
+    /// Makes the transaction a transfer of `amount` to the address `to`
+    pub fn transfer(mut self, to: impl Into<String>, amount: i64) -> TransactionBuilder {
+        self.transaction_type = Some(TransactionType::Transfer { to: to.into() });
+        self.amount = Some(amount);
+        self
+    }
+
+    /// Sets the wallet address of the transaction (the source of a transfer)
+    pub fn wallet(mut self, wallet_address: impl Into<String>) -> TransactionBuilder {
+        self.wallet_address = Some(wallet_address.into());
+        self
+    }
+
+    /// Denominates the transaction in an SPL token instead of native SOL
+    pub fn mint(mut self, mint: impl Into<String>) -> TransactionBuilder {
+        self.mint = Some(mint.into());
+        self
+    }
+
+    /// Sets the network fee paid by the wallet
+    pub fn fee(mut self, fee: i64) -> TransactionBuilder {
+        self.fee = Some(fee);
+        self
+    }
+
+    /// Sets the on-chain signature identifying the transaction
+    pub fn signature(mut self, signature: impl Into<String>) -> TransactionBuilder {
+        self.signature = signature.into();
+        self
+    }
+
+    /// Sets whether the transaction is pending, confirmed, or failed
+    pub fn status(mut self, status: TransactionStatus) -> TransactionBuilder {
+        self.status = status;
+        self
+    }
+
+    /// Sets how many blocks have been confirmed on top of the transaction's block
+    pub fn confirmations(mut self, confirmations: u32) -> TransactionBuilder {
+        self.confirmations = confirmations;
+        self
+    }
+
+    /// Attaches a free-form memo to the transaction
+    pub fn memo(mut self, memo: impl Into<String>) -> TransactionBuilder {
+        self.memo = Some(memo.into());
+        self
+    }
+
+    /// Attaches the raw ed25519 signature and the message it was made over
+    pub fn signed_message(mut self, signature_bytes: [u8; 64], message_bytes: impl Into<Vec<u8>>) -> TransactionBuilder {
+        self.signature_bytes = Some(signature_bytes);
+        self.message_bytes = Some(message_bytes.into());
+        self
+    }
+
+    /// Sets when the transaction happened, in Unix seconds
+    pub fn timestamp(mut self, timestamp: i64) -> TransactionBuilder {
+        self.timestamp = timestamp;
+        self
+    }
+
+    /// Builds the transaction, validating every field
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Transaction)` - The validated transaction
+    /// * `Err(TransactionError)` - If a required field is missing or any field is invalid
+    ///
+    pub fn build(self) -> Result<Transaction, TransactionError> {
+        let (Some(transaction_type), Some(amount)) = (self.transaction_type, self.amount) else {
+            return Err(TransactionError::MissingField("transaction type"));
+        };
+        let wallet_address = self.wallet_address.ok_or(TransactionError::MissingField("wallet"))?;
+
+        let tx = Transaction {
+            transaction_type,
+            wallet_address,
+            amount,
+            mint: self.mint,
+            timestamp: self.timestamp,
+            fee: self.fee,
+            signature: self.signature,
+            status: self.status,
+            memo: self.memo,
+            signature_bytes: self.signature_bytes,
+            message_bytes: self.message_bytes,
+            confirmations: self.confirmations,
+        };
+        tx.validate()?;
+        Ok(tx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALICE: &str = "ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3";
+
+    /// Tests building a transaction fluently and reading back its fields
+    #[test]
+    fn test_build_transaction() {
+        let tx = TransactionBuilder::new()
+            .withdrawal(250)
+            .wallet(ALICE)
+            .fee(5)
+            .timestamp(1_700_000_000)
+            .build()
+            .unwrap();
+
+        assert_eq!(tx.transaction_type(), &TransactionType::Withdrawal);
+        assert_eq!(tx.wallet_address(), ALICE);
+        assert_eq!(tx.amount(), 250);
+        assert_eq!(tx.fee(), Some(5));
+        assert_eq!(tx.timestamp(), 1_700_000_000);
+        assert_eq!(tx.mint(), None);
+    }
+
+    /// Tests that building runs the usual validations
+    #[test]
+    fn test_build_invalid_transaction() {
+        assert!(matches!(
+            TransactionBuilder::new().deposit(0).wallet(ALICE).build(),
+            Err(TransactionError::ZeroAmount)
+        ));
+        assert!(matches!(
+            TransactionBuilder::new().deposit(10).wallet(ALICE).fee(-1).build(),
+            Err(TransactionError::NegativeFee(-1))
+        ));
+        assert!(matches!(
+            TransactionBuilder::new().deposit(10).build(),
+            Err(TransactionError::MissingField("wallet"))
+        ));
+    }
+}
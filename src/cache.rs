@@ -0,0 +1,100 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+use crate::balance::calculate_native_balances;
+use crate::error::TransactionError;
+use crate::transaction::Transaction;
+
+/// Memoizes the native balances of the most recently seen transaction history
+///
+/// Interactive tools often recompute balances for a history that has not changed. The
+/// cache fingerprints the history on each call and only recalculates when the
+/// fingerprint differs from the cached one.
+#[derive(Debug, Default)]
+pub struct BalanceCache {
+    /// The fingerprint of the history the cached balances were calculated from
+    fingerprint: Option<u64>,
+    /// The native balance of each wallet in the cached history
+    balances: HashMap<String, i64>,
+    /// How many calls were answered from the cache
+    hits: usize,
+}
+
+impl BalanceCache {
+    /// Creates an empty cache
+    pub fn new() -> BalanceCache {
+        BalanceCache::default()
+    }
+
+    /// Returns the native balance of every wallet, recalculating only if the history changed
+    ///
+    /// # Arguments
+    ///
+    /// * `transactions` - A slice of transactions to process
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(&HashMap<String, i64>)` - The native balance of each wallet, keyed by address
+    /// * `Err(TransactionError)` - If any transaction is invalid; the cache is left unchanged
+    ///
+    pub fn balances(&mut self, transactions: &[Transaction]) -> Result<&HashMap<String, i64>, TransactionError> {
+        let fingerprint = fingerprint(transactions);
+        if self.fingerprint == Some(fingerprint) {
+            self.hits += 1;
+        } else {
+            self.balances = calculate_native_balances(transactions)?;
+            self.fingerprint = Some(fingerprint);
+        }
+        Ok(&self.balances)
+    }
+
+    /// Returns how many calls were answered from the cache
+    pub fn hits(&self) -> usize {
+        self.hits
+    }
+}
+
+/// Hashes every field of the history that affects a balance
+fn fingerprint(transactions: &[Transaction]) -> u64 {
+    // DefaultHasher::new() uses fixed keys, so equal histories always hash equally
+    let mut hasher = DefaultHasher::new();
+    transactions.len().hash(&mut hasher);
+    for tx in transactions {
+        tx.signature.hash(&mut hasher);
+        tx.transaction_type.hash(&mut hasher);
+        tx.wallet_address.hash(&mut hasher);
+        tx.amount.hash(&mut hasher);
+        tx.mint.hash(&mut hasher);
+        tx.fee.hash(&mut hasher);
+        tx.status.hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transaction::TransactionType;
+
+    const ALICE: &str = "ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3";
+
+    /// Tests that identical input hits the cache and changed input is recalculated
+    #[test]
+    fn test_balance_cache() {
+        let mut transactions = vec![
+            Transaction::new(TransactionType::Deposit, ALICE, 100).unwrap().with_signature("sig-1"),
+            Transaction::new(TransactionType::Withdrawal, ALICE, 30).unwrap().with_signature("sig-2"),
+        ];
+        let mut cache = BalanceCache::new();
+
+        assert_eq!(cache.balances(&transactions).unwrap()[ALICE], 70);
+        assert_eq!(cache.hits(), 0);
+        assert_eq!(cache.balances(&transactions).unwrap()[ALICE], 70);
+        assert_eq!(cache.hits(), 1);
+
+        transactions[1].amount = 40;
+        assert_eq!(cache.balances(&transactions).unwrap()[ALICE], 60);
+        assert_eq!(cache.hits(), 1);
+    }
+}
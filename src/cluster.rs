@@ -0,0 +1,75 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::TransactionError;
+
+/// The Solana network a batch of transactions belongs to
+///
+/// Addresses are valid on every cluster, so this is a label rather than a validation rule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Cluster {
+    /// The production network, also known as `mainnet-beta`
+    #[default]
+    Mainnet,
+    /// The developer network, where SOL can be airdropped freely
+    Devnet,
+    /// The network used to stress-test upcoming releases
+    Testnet,
+}
+
+impl FromStr for Cluster {
+    type Err = TransactionError;
+
+    /// Parses a cluster name case-insensitively, accepting `mainnet-beta` as an alias for `mainnet`
+    fn from_str(s: &str) -> Result<Cluster, TransactionError> {
+        match s.to_ascii_lowercase().as_str() {
+            "mainnet" | "mainnet-beta" => Ok(Cluster::Mainnet),
+            "devnet" => Ok(Cluster::Devnet),
+            "testnet" => Ok(Cluster::Testnet),
+            _ => Err(TransactionError::UnknownCluster(s.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for Cluster {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Cluster::Mainnet => "mainnet",
+            Cluster::Devnet => "devnet",
+            Cluster::Testnet => "testnet",
+        };
+        f.write_str(name)
+    }
+}
+
+/// A request for the balance of one wallet on a particular cluster
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BalanceRequest {
+    /// The network the wallet's transactions were recorded on
+    pub cluster: Cluster,
+    /// The address of the wallet
+    pub wallet: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests parsing every cluster name, including the `mainnet-beta` alias and mixed case
+    #[test]
+    fn test_parse_clusters() {
+        assert_eq!("mainnet".parse::<Cluster>().unwrap(), Cluster::Mainnet);
+        assert_eq!("mainnet-beta".parse::<Cluster>().unwrap(), Cluster::Mainnet);
+        assert_eq!("Devnet".parse::<Cluster>().unwrap(), Cluster::Devnet);
+        assert_eq!("TESTNET".parse::<Cluster>().unwrap(), Cluster::Testnet);
+    }
+
+    /// Tests that an unknown cluster name is rejected
+    #[test]
+    fn test_parse_unknown_cluster() {
+        assert!(matches!(
+            "localnet".parse::<Cluster>(),
+            Err(TransactionError::UnknownCluster(name)) if name == "localnet"
+        ));
+    }
+}
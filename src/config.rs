@@ -0,0 +1,87 @@
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::TransactionError;
+
+/// Configurable rules for validating transactions while calculating a balance
+///
+/// Every field is optional in TOML; anything left out keeps its default, which applies no
+/// extra rule beyond those of [`calculate_wallet_balance`](crate::calculate_wallet_balance).
+///
+/// ```toml
+/// min_amount = 1_000
+/// max_amount = 5_000_000_000
+/// min_balance = 890_880
+/// allow_negative = false
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ValidationConfig {
+    /// The smallest amount a single transaction may carry
+    pub min_amount: Option<i64>,
+    /// The largest amount a single transaction may carry
+    pub max_amount: Option<i64>,
+    /// The running balance withdrawals may not drop the wallet below
+    pub min_balance: Option<i64>,
+    /// Whether withdrawals may push the running balance below zero
+    pub allow_negative: bool,
+}
+
+impl Default for ValidationConfig {
+    fn default() -> ValidationConfig {
+        ValidationConfig {
+            min_amount: None,
+            max_amount: None,
+            min_balance: None,
+            allow_negative: true,
+        }
+    }
+}
+
+impl ValidationConfig {
+    /// Loads validation rules from a TOML file
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the TOML file
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(ValidationConfig)` - The loaded rules
+    /// * `Err(TransactionError)` - If the file cannot be read or is not valid TOML
+    ///
+    pub fn from_toml(path: impl AsRef<Path>) -> Result<ValidationConfig, TransactionError> {
+        let contents = fs::read_to_string(path)?;
+        Ok(toml::from_str(&contents)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests that fields left out of the TOML keep their defaults
+    #[test]
+    fn test_partial_toml() {
+        let config: ValidationConfig = toml::from_str("max_amount = 500").unwrap();
+        assert_eq!(
+            config,
+            ValidationConfig {
+                max_amount: Some(500),
+                ..ValidationConfig::default()
+            }
+        );
+    }
+
+    /// Tests that malformed TOML is reported
+    #[test]
+    fn test_malformed_toml() {
+        let path = std::env::temp_dir().join("ryz_malformed_validation_config.toml");
+        fs::write(&path, "max_amount = \"lots\"").unwrap();
+        let result = ValidationConfig::from_toml(&path);
+        fs::remove_file(&path).unwrap();
+        assert!(matches!(result, Err(TransactionError::Toml(_))));
+    }
+}
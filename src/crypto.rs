@@ -0,0 +1,78 @@
+use ed25519_dalek::{Signature, VerifyingKey};
+
+use crate::error::TransactionError;
+use crate::transaction::Transaction;
+
+/// Verifies that a transaction's message was signed by its wallet's key
+///
+/// A Solana address is the base58 encoding of the wallet's ed25519 public key, so the
+/// wallet address is decoded and used to check `signature_bytes` against `message_bytes`.
+///
+/// # Arguments
+///
+/// * `tx` - The transaction to verify
+///
+/// # Returns
+///
+/// * `Ok(bool)` - Whether the signature is valid for the message and wallet
+/// * `Err(TransactionError)` - `MissingField` if the signature or message is absent, or
+///   `InvalidWalletAddress` if the wallet address is not a valid ed25519 public key
+///
+pub fn verify_signature(tx: &Transaction) -> Result<bool, TransactionError> {
+    let signature = tx.signature_bytes.ok_or(TransactionError::MissingField("signature bytes"))?;
+    let message = tx
+        .message_bytes
+        .as_deref()
+        .ok_or(TransactionError::MissingField("message bytes"))?;
+
+    let invalid_address = || TransactionError::InvalidWalletAddress(tx.wallet_address.clone());
+    let key_bytes: [u8; 32] = bs58::decode(&tx.wallet_address)
+        .into_vec()
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(invalid_address)?;
+    let key = VerifyingKey::from_bytes(&key_bytes).map_err(|_| invalid_address())?;
+
+    Ok(key.verify_strict(message, &Signature::from_bytes(&signature)).is_ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transaction::TransactionType;
+    use ed25519_dalek::{Signer, SigningKey};
+
+    /// Tests verifying a signature made with a known keypair, and rejecting a tampered message
+    #[test]
+    fn test_verify_signature() {
+        let signing_key = SigningKey::from_bytes(&[7; 32]);
+        let wallet = bs58::encode(signing_key.verifying_key().as_bytes()).into_string();
+        let message = b"deposit 100 lamports".to_vec();
+        let signature = signing_key.sign(&message).to_bytes();
+
+        let tx = Transaction::new(TransactionType::Deposit, wallet.as_str(), 100)
+            .unwrap()
+            .with_signed_message(signature, message);
+        assert!(verify_signature(&tx).unwrap());
+
+        let tampered = tx.clone().with_signed_message(signature, b"deposit 900 lamports".to_vec());
+        assert!(!verify_signature(&tampered).unwrap());
+
+        let unsigned = Transaction::new(TransactionType::Deposit, wallet.as_str(), 100).unwrap();
+        assert!(matches!(verify_signature(&unsigned), Err(TransactionError::MissingField(_))));
+    }
+
+    /// Tests that a signature survives a round trip through JSON as a base58 string
+    #[test]
+    fn test_signature_bytes_round_trip() {
+        let signing_key = SigningKey::from_bytes(&[7; 32]);
+        let wallet = bs58::encode(signing_key.verifying_key().as_bytes()).into_string();
+        let tx = Transaction::new(TransactionType::Deposit, wallet.as_str(), 100)
+            .unwrap()
+            .with_signed_message(signing_key.sign(b"hello").to_bytes(), b"hello".to_vec());
+
+        let restored: Transaction = serde_json::from_str(&serde_json::to_string(&tx).unwrap()).unwrap();
+        assert_eq!(restored, tx);
+        assert!(verify_signature(&restored).unwrap());
+    }
+}
@@ -0,0 +1,219 @@
+use std::fmt;
+use std::str::FromStr;
+
+use rust_decimal::Decimal;
+
+use crate::address::{normalize_wallet_address, validate_address};
+use crate::error::TransactionError;
+use crate::transaction::TransactionType;
+
+/// A positive fractional amount, held exactly as a [`Decimal`]
+///
+/// Amounts such as `0.1` have no exact `f64` representation, so summing them as floats
+/// drifts. A `DecimalAmount` keeps every digit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct DecimalAmount(Decimal);
+
+impl DecimalAmount {
+    /// Wraps a decimal amount, which must be positive
+    ///
+    /// # Arguments
+    ///
+    /// * `amount` - The amount to wrap
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(DecimalAmount)` - The validated amount
+    /// * `Err(TransactionError)` - If the amount is zero or negative
+    ///
+    pub fn new(amount: Decimal) -> Result<DecimalAmount, TransactionError> {
+        if amount <= Decimal::ZERO {
+            return Err(TransactionError::InvalidDecimalAmount(amount.to_string()));
+        }
+        Ok(DecimalAmount(amount))
+    }
+
+    /// Returns the wrapped amount
+    pub fn value(&self) -> Decimal {
+        self.0
+    }
+}
+
+impl FromStr for DecimalAmount {
+    type Err = TransactionError;
+
+    /// Parses a decimal string such as `"0.1"`, which must be positive
+    fn from_str(s: &str) -> Result<DecimalAmount, TransactionError> {
+        let amount = Decimal::from_str(s).map_err(|_| TransactionError::InvalidDecimalAmount(s.to_string()))?;
+        DecimalAmount::new(amount)
+    }
+}
+
+impl fmt::Display for DecimalAmount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// A transaction whose amount is a fractional [`DecimalAmount`] rather than raw lamports
+///
+/// This carries amounts for [`calculate_wallet_balance_decimal`]. Mints, fees, and
+/// statuses are not tracked.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecimalTransaction {
+    /// Whether the transaction adds, removes, or transfers funds
+    transaction_type: TransactionType,
+    /// The wallet address associated with the transaction (the source of a transfer)
+    wallet_address: String,
+    /// The amount of the transaction
+    amount: DecimalAmount,
+}
+
+impl DecimalTransaction {
+    /// Creates a new transaction, validating its addresses up front
+    ///
+    /// # Arguments
+    ///
+    /// * `transaction_type` - Whether the transaction adds, removes, or transfers funds
+    /// * `wallet_address` - The Solana address of the wallet (the source of a transfer)
+    /// * `amount` - The amount of the transaction
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(DecimalTransaction)` - The validated transaction
+    /// * `Err(TransactionError)` - If an address is invalid
+    ///
+    pub fn new(
+        transaction_type: TransactionType,
+        wallet_address: impl Into<String>,
+        amount: DecimalAmount,
+    ) -> Result<DecimalTransaction, TransactionError> {
+        let wallet_address = wallet_address.into();
+        validate_address(&wallet_address)?;
+        if let TransactionType::Transfer { to } = &transaction_type {
+            validate_address(to)?;
+            if *to == wallet_address {
+                return Err(TransactionError::SelfTransfer(to.clone()));
+            }
+        }
+        Ok(DecimalTransaction {
+            transaction_type,
+            wallet_address,
+            amount,
+        })
+    }
+
+    /// Returns the type of the transaction
+    pub fn transaction_type(&self) -> &TransactionType {
+        &self.transaction_type
+    }
+
+    /// Returns the wallet address associated with the transaction
+    pub fn wallet_address(&self) -> &str {
+        &self.wallet_address
+    }
+
+    /// Returns the amount of the transaction
+    pub fn amount(&self) -> DecimalAmount {
+        self.amount
+    }
+}
+
+/// Calculates the balance of a wallet exactly from transactions with decimal amounts
+///
+/// # Arguments
+///
+/// * `wallet_address` - The address of the wallet to calculate the balance for
+/// * `transactions` - A slice of transactions to process
+///
+/// # Returns
+///
+/// * `Ok(Decimal)` - The calculated balance if successful
+/// * `Err(TransactionError)` - If the address is invalid, no transaction involves the
+///   wallet, or the balance overflows
+///
+pub fn calculate_wallet_balance_decimal(
+    wallet_address: &str,
+    transactions: &[DecimalTransaction],
+) -> Result<Decimal, TransactionError> {
+    let wallet_address = normalize_wallet_address(wallet_address)?;
+
+    let mut seen_any = false;
+    let balance = transactions
+        .iter()
+        .filter(|tx| {
+            tx.wallet_address == wallet_address
+                || matches!(&tx.transaction_type, TransactionType::Transfer { to } if *to == wallet_address)
+        })
+        .inspect(|_| seen_any = true)
+        .enumerate()
+        .try_fold(Decimal::ZERO, |acc, (position, tx)| {
+            let amount = tx.amount.value();
+            let updated = match &tx.transaction_type {
+                TransactionType::Deposit | TransactionType::StakingReward => acc.checked_add(amount),
+                TransactionType::Withdrawal | TransactionType::Fee => acc.checked_sub(amount),
+                TransactionType::OpeningBalance if position == 0 => Some(amount),
+                TransactionType::OpeningBalance => {
+                    return Err(TransactionError::MisplacedOpeningBalance(wallet_address.clone()))
+                }
+                TransactionType::Transfer { .. } if tx.wallet_address == wallet_address => acc.checked_sub(amount),
+                TransactionType::Transfer { .. } => acc.checked_add(amount),
+            };
+            updated.ok_or_else(|| TransactionError::BalanceOverflow(wallet_address.clone()))
+        })?;
+
+    // Check if any transactions involve the wallet
+    if !seen_any {
+        return Err(TransactionError::NoTransactions(wallet_address));
+    }
+    Ok(balance)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALICE: &str = "ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3";
+
+    /// Builds a transaction for Alice from a decimal string
+    fn alice(transaction_type: TransactionType, amount: &str) -> DecimalTransaction {
+        DecimalTransaction::new(transaction_type, ALICE, amount.parse().unwrap()).unwrap()
+    }
+
+    /// Tests that 0.1 + 0.2 sums to exactly 0.3
+    #[test]
+    fn test_decimal_sum_is_exact() {
+        let transactions = vec![
+            alice(TransactionType::Deposit, "0.1"),
+            alice(TransactionType::Deposit, "0.2"),
+        ];
+        assert_eq!(
+            calculate_wallet_balance_decimal(ALICE, &transactions).unwrap(),
+            Decimal::from_str("0.3").unwrap()
+        );
+    }
+
+    /// Tests a decimal withdrawal that leaves a fractional balance
+    #[test]
+    fn test_decimal_withdrawal() {
+        let transactions = vec![
+            alice(TransactionType::Deposit, "1.000001"),
+            alice(TransactionType::Withdrawal, "0.7"),
+        ];
+        assert_eq!(
+            calculate_wallet_balance_decimal(ALICE, &transactions).unwrap().to_string(),
+            "0.300001"
+        );
+    }
+
+    /// Tests that zero, negative, and malformed amounts are rejected
+    #[test]
+    fn test_invalid_decimal_amounts() {
+        for input in ["0", "-0.5", "lots"] {
+            assert!(matches!(
+                input.parse::<DecimalAmount>(),
+                Err(TransactionError::InvalidDecimalAmount(_))
+            ));
+        }
+    }
+}
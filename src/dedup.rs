@@ -0,0 +1,188 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::history::sort_transactions;
+use crate::transaction::{Transaction, TransactionType};
+
+/// Removes duplicate transactions, keeping the first occurrence of each signature
+///
+/// Merging feeds from several RPC providers often yields the same transaction twice,
+/// which would otherwise be double-counted. Transactions without a signature cannot be
+/// identified and are always kept.
+///
+/// # Arguments
+///
+/// * `transactions` - A slice of transactions that may contain duplicates
+///
+/// # Returns
+///
+/// * `Vec<Transaction>` - The unique transactions, in their original order
+///
+pub fn dedup_transactions(transactions: &[Transaction]) -> Vec<Transaction> {
+    unique_by_signature(transactions).map(|(_, tx)| tx.clone()).collect()
+}
+
+/// Combines several transaction feeds into one deduplicated, time-ordered history
+///
+/// Sources are concatenated in order, so when the same signature appears in more than one
+/// source the version from the earliest source is kept. The result is then put into the
+/// canonical order of [`sort_transactions`](crate::sort_transactions).
+///
+/// # Arguments
+///
+/// * `sources` - The transaction feeds to merge, e.g. one per CSV or JSON import
+///
+/// # Returns
+///
+/// * `Vec<Transaction>` - The unique transactions from every source, sorted by timestamp
+///
+pub fn merge_sources(sources: Vec<Vec<Transaction>>) -> Vec<Transaction> {
+    let combined: Vec<Transaction> = sources.into_iter().flatten().collect();
+    let mut merged = dedup_transactions(&combined);
+    sort_transactions(&mut merged);
+    merged
+}
+
+/// Compares two transaction feeds by signature, finding the transactions each one is missing
+///
+/// Transactions without a signature cannot be matched and are always reported as unique
+/// to their feed.
+///
+/// # Arguments
+///
+/// * `a` - The first feed
+/// * `b` - The second feed
+///
+/// # Returns
+///
+/// * `(Vec<Transaction>, Vec<Transaction>)` - The transactions only in `a` and those only in `b`,
+///   each in its feed's order
+///
+pub fn diff_transactions(a: &[Transaction], b: &[Transaction]) -> (Vec<Transaction>, Vec<Transaction>) {
+    let only_in = |feed: &[Transaction], other: &[Transaction]| {
+        let signatures: HashSet<&str> = other.iter().map(|tx| tx.signature.as_str()).collect();
+        feed.iter()
+            .filter(|tx| tx.signature.is_empty() || !signatures.contains(tx.signature.as_str()))
+            .cloned()
+            .collect()
+    };
+    (only_in(a, b), only_in(b, a))
+}
+
+/// Finds pairs of transactions that look like the same transaction imported twice
+///
+/// Two transactions are suspected duplicates when they share a wallet, type, amount, and
+/// timestamp, whatever their signatures. Three or more matching transactions yield
+/// every pair among them.
+///
+/// # Arguments
+///
+/// * `transactions` - A slice of transactions to check
+///
+/// # Returns
+///
+/// * `Vec<(usize, usize)>` - The slice indices of each suspected pair, lower index first,
+///   in ascending order
+///
+pub fn find_suspected_duplicates(transactions: &[Transaction]) -> Vec<(usize, usize)> {
+    let mut groups: HashMap<(&str, &TransactionType, i64, i64), Vec<usize>> = HashMap::new();
+    for (index, tx) in transactions.iter().enumerate() {
+        let key = (tx.wallet_address.as_str(), &tx.transaction_type, tx.amount, tx.timestamp);
+        groups.entry(key).or_default().push(index);
+    }
+
+    let mut pairs: Vec<(usize, usize)> = groups
+        .values()
+        .flat_map(|indices| {
+            indices
+                .iter()
+                .enumerate()
+                .flat_map(|(n, &first)| indices[n + 1..].iter().map(move |&second| (first, second)))
+        })
+        .collect();
+    pairs.sort_unstable();
+    pairs
+}
+
+/// Iterates over the first occurrence of each signature, plus every unsigned transaction,
+/// along with each transaction's index in the slice
+pub(crate) fn unique_by_signature(transactions: &[Transaction]) -> impl Iterator<Item = (usize, &Transaction)> {
+    let mut seen = HashSet::new();
+    transactions
+        .iter()
+        .enumerate()
+        .filter(move |(_, tx)| tx.signature.is_empty() || seen.insert(tx.signature.as_str()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALICE: &str = "ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3";
+    const BOB: &str = "9jLkNAaW9E47LQMHvjohy2uAAyr1331bAxgJKFRU7wF6";
+
+    /// Tests that the first occurrence of a duplicated signature is kept
+    #[test]
+    fn test_dedup_transactions() {
+        let transactions = vec![
+            Transaction::new(TransactionType::Deposit, ALICE, 100).unwrap().with_signature("sig-1"),
+            Transaction::new(TransactionType::Deposit, ALICE, 50).unwrap().with_signature("sig-2"),
+            Transaction::new(TransactionType::Deposit, ALICE, 999).unwrap().with_signature("sig-1"),
+            Transaction::new(TransactionType::Deposit, ALICE, 7).unwrap(),
+            Transaction::new(TransactionType::Deposit, ALICE, 7).unwrap(),
+        ];
+
+        let unique = dedup_transactions(&transactions);
+        let amounts: Vec<i64> = unique.iter().map(Transaction::amount).collect();
+        assert_eq!(amounts, vec![100, 50, 7, 7]);
+    }
+
+    /// Tests merging two overlapping sources into one time-ordered history
+    #[test]
+    fn test_merge_sources() {
+        let first = vec![
+            Transaction::new(TransactionType::Deposit, ALICE, 100).unwrap().with_timestamp(300).with_signature("sig-3"),
+            Transaction::new(TransactionType::Deposit, ALICE, 10).unwrap().with_timestamp(100).with_signature("sig-1"),
+        ];
+        let second = vec![
+            Transaction::new(TransactionType::Deposit, ALICE, 999).unwrap().with_timestamp(100).with_signature("sig-1"),
+            Transaction::new(TransactionType::Deposit, ALICE, 20).unwrap().with_timestamp(200).with_signature("sig-2"),
+        ];
+
+        let merged = merge_sources(vec![first, second]);
+        let amounts: Vec<i64> = merged.iter().map(Transaction::amount).collect();
+        assert_eq!(amounts, vec![10, 20, 100]);
+    }
+
+    /// Tests that each feed's unique transactions are reported, and shared ones are not
+    #[test]
+    fn test_diff_transactions() {
+        let shared = Transaction::new(TransactionType::Deposit, ALICE, 100).unwrap().with_signature("sig-1");
+        let a = vec![
+            shared.clone(),
+            Transaction::new(TransactionType::Deposit, ALICE, 20).unwrap().with_signature("sig-2"),
+        ];
+        let b = vec![
+            Transaction::new(TransactionType::Withdrawal, ALICE, 5).unwrap().with_signature("sig-3"),
+            shared,
+        ];
+
+        let (only_in_a, only_in_b) = diff_transactions(&a, &b);
+        assert_eq!(only_in_a, vec![a[1].clone()]);
+        assert_eq!(only_in_b, vec![b[0].clone()]);
+    }
+
+    /// Tests that only the pair matching on wallet, type, amount, and timestamp is flagged
+    #[test]
+    fn test_find_suspected_duplicates() {
+        let transactions = vec![
+            Transaction::new(TransactionType::Deposit, ALICE, 100).unwrap().with_timestamp(10).with_signature("sig-1"),
+            Transaction::new(TransactionType::Deposit, ALICE, 100).unwrap().with_timestamp(20),
+            Transaction::new(TransactionType::Withdrawal, ALICE, 100).unwrap().with_timestamp(10),
+            Transaction::new(TransactionType::Deposit, BOB, 100).unwrap().with_timestamp(10),
+            Transaction::new(TransactionType::Deposit, ALICE, 100).unwrap().with_timestamp(10).with_signature("sig-2"),
+            Transaction::new(TransactionType::Deposit, ALICE, 99).unwrap().with_timestamp(10),
+        ];
+
+        assert_eq!(find_suspected_duplicates(&transactions), vec![(0, 4)]);
+    }
+}
@@ -0,0 +1,276 @@
+use thiserror::Error;
+
+/// Represents possible errors that can occur during transaction processing
+#[derive(Error, Debug)]
+pub enum TransactionError {
+    /// Returned when the wallet address is invalid or empty
+    #[error("Invalid wallet address: {0}")]
+    InvalidWalletAddress(String),
+    /// Returned when a transaction fee is negative
+    #[error("Fee cannot be negative: {0}")]
+    NegativeFee(i64),
+    /// Returned when an SPL token mint address is invalid
+    #[error("Invalid mint address: {0}")]
+    InvalidMintAddress(String),
+    /// Returned when a transaction amount is zero
+    #[error("Amount cannot be zero")]
+    ZeroAmount,
+    /// Returned when a transaction amount is negative; the transaction type encodes direction
+    #[error("Amount cannot be negative: {0}")]
+    NegativeAmount(i64),
+    #[error("No transactions found for wallet {0}")]
+    NoTransactions(String),
+    /// Returned when a string does not name a known transaction type
+    #[error("Unknown transaction type: {0}")]
+    UnknownTransactionType(String),
+    /// Returned when a wallet has more transactions than an import allows
+    #[error("Wallet {wallet} has {count} transactions, more than the limit of {max}")]
+    TooManyTransactions { wallet: String, count: usize, max: usize },
+    /// Returned when an opening balance is not the first transaction for its wallet
+    #[error("Opening balance for wallet {0} must be its first transaction")]
+    MisplacedOpeningBalance(String),
+    /// Returned when a frozen wallet withdraws or transfers funds out
+    #[error("Wallet {0} is frozen")]
+    WalletFrozen(String),
+    /// Returned when a transaction applied to a wallet does not involve that wallet
+    #[error("Transaction for {transaction_wallet} does not involve wallet {wallet}")]
+    WalletMismatch { wallet: String, transaction_wallet: String },
+    /// Returned when a fee multiplier is negative or not a finite number
+    #[error("Fee multiplier must be a non-negative finite number: {0}")]
+    InvalidFeeMultiplier(f64),
+    /// Returned when a CSV row's amount is not an integer, keeping the parse error as its source
+    #[error("Invalid amount in CSV row at line {line}: {source}")]
+    ParseRow {
+        line: u64,
+        #[source]
+        source: std::num::ParseIntError,
+    },
+    /// Returned when a CSV row cannot be read, keeping the CSV error as its source
+    #[error("Malformed CSV row at line {line}: {source}")]
+    Csv {
+        line: u64,
+        #[source]
+        source: csv::Error,
+    },
+    /// Returned when a timestamp is too close to the edge of the `i64` range to bucket into a day
+    #[error("Timestamp {0} is out of range")]
+    TimestampOutOfRange(i64),
+    /// Returned when a transfer's destination is its own source wallet
+    #[error("Wallet {0} cannot transfer to itself")]
+    SelfTransfer(String),
+    /// Returned when a string does not name a known Solana cluster
+    #[error("Unknown cluster {0:?}, expected mainnet, devnet, or testnet")]
+    UnknownCluster(String),
+    /// Returned when a transaction builder is missing a required field
+    #[error("Missing transaction field: {0}")]
+    MissingField(&'static str),
+    /// Returned when an operation cannot handle a transaction of the given type
+    #[error("Unsupported transaction type for this operation: {0}")]
+    UnsupportedTransactionType(String),
+    /// Returned when a compact transaction string is malformed
+    #[error("Cannot parse transaction: {0}")]
+    ParseError(String),
+    /// Returned when an integer field, such as an amount, cannot be parsed
+    #[error("Invalid integer: {0}")]
+    Parse(#[from] std::num::ParseIntError),
+    /// Returned when a transaction file cannot be read
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    /// Returned when the transaction at `index` of the input fails validation
+    #[error("Invalid transaction at index {index}: {source}")]
+    InvalidTransaction {
+        index: usize,
+        #[source]
+        source: Box<TransactionError>,
+    },
+    /// Returned when transaction JSON is malformed
+    #[error("Invalid transaction JSON: {0}")]
+    Json(#[from] serde_json::Error),
+    /// Returned when a TOML configuration file is malformed
+    #[error("Invalid TOML configuration: {0}")]
+    Toml(#[from] toml::de::Error),
+    /// Returned when a CSV row cannot be parsed into a valid transaction
+    #[error("Invalid CSV row at line {line}: {reason}")]
+    InvalidCsvRow { line: u64, reason: String },
+    /// Returned when a withdrawal would push the running balance below zero
+    #[error("Insufficient funds in wallet {wallet}: balance {balance}, attempted withdrawal {attempted}")]
+    InsufficientFunds { wallet: String, balance: i64, attempted: i64 },
+    /// Returned when a withdrawal would drop the balance below the rent-exempt minimum
+    #[error("Wallet {wallet} would fall below its rent-exempt minimum: balance {balance}, minimum {min}")]
+    BelowRentExempt { wallet: String, balance: i64, min: i64 },
+    /// Returned when a SOL amount cannot be represented as a whole number of lamports
+    #[error("SOL amount {0} is not a whole number of lamports within range")]
+    InvalidSolAmount(f64),
+    /// Returned when a single transaction is smaller than the configured minimum
+    #[error("Amount {amount} is below the minimum of {min}")]
+    AmountBelowMinimum { amount: i64, min: i64 },
+    /// Returned when a single transaction is larger than the configured maximum
+    #[error("Amount {amount} exceeds the limit of {max}")]
+    AmountExceedsLimit { amount: i64, max: i64 },
+    /// Returned when a decimal amount is malformed, zero, or negative
+    #[error("Invalid decimal amount: {0}")]
+    InvalidDecimalAmount(String),
+    /// Returned when a histogram bucket size is zero or negative
+    #[error("Bucket size must be positive: {0}")]
+    InvalidBucketSize(i64),
+    /// Returned when a percentage is above 100
+    #[error("Percentage cannot exceed 100: {0}")]
+    InvalidPercentage(u8),
+    /// Returned when the interest accrued on a principal does not fit in an i64
+    #[error("Interest on principal {0} overflows")]
+    InterestOverflow(i64),
+    /// Returned when applying a transaction would overflow or underflow the balance
+    #[error("Balance overflow for wallet {0}")]
+    BalanceOverflow(String),
+}
+
+impl TransactionError {
+    /// Returns a stable process exit code identifying the kind of error
+    ///
+    /// Codes start at 2 so they never collide with success (0) or a generic failure (1).
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            TransactionError::InvalidWalletAddress(_) => 2,
+            TransactionError::ZeroAmount => 3,
+            TransactionError::NoTransactions(_) => 4,
+            TransactionError::NegativeAmount(_) => 5,
+            TransactionError::NegativeFee(_) => 6,
+            TransactionError::InvalidMintAddress(_) => 7,
+            TransactionError::UnknownTransactionType(_) => 8,
+            TransactionError::InsufficientFunds { .. } => 9,
+            TransactionError::BalanceOverflow(_) => 10,
+            TransactionError::Io(_) => 11,
+            TransactionError::Json(_) => 12,
+            TransactionError::InvalidCsvRow { .. } => 13,
+            TransactionError::MissingField(_) => 14,
+            TransactionError::BelowRentExempt { .. } => 15,
+            TransactionError::InvalidSolAmount(_) => 16,
+            TransactionError::ParseError(_) => 17,
+            TransactionError::UnsupportedTransactionType(_) => 18,
+            TransactionError::AmountExceedsLimit { .. } => 19,
+            TransactionError::UnknownCluster(_) => 20,
+            TransactionError::InterestOverflow(_) => 21,
+            TransactionError::InvalidTransaction { .. } => 22,
+            TransactionError::Toml(_) => 23,
+            TransactionError::AmountBelowMinimum { .. } => 24,
+            TransactionError::InvalidPercentage(_) => 25,
+            TransactionError::InvalidDecimalAmount(_) => 26,
+            TransactionError::SelfTransfer(_) => 27,
+            TransactionError::TooManyTransactions { .. } => 28,
+            TransactionError::Parse(_) => 29,
+            TransactionError::InvalidBucketSize(_) => 30,
+            TransactionError::MisplacedOpeningBalance(_) => 31,
+            TransactionError::WalletFrozen(_) => 32,
+            TransactionError::WalletMismatch { .. } => 33,
+            TransactionError::InvalidFeeMultiplier(_) => 34,
+            TransactionError::ParseRow { .. } => 35,
+            TransactionError::Csv { .. } => 36,
+            TransactionError::TimestampOutOfRange(_) => 37,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests that every variant maps to its documented exit code
+    #[test]
+    fn test_exit_codes() {
+        let json_error = serde_json::from_str::<i64>("not json").unwrap_err();
+        let toml_error = toml::from_str::<crate::ValidationConfig>("not toml").unwrap_err();
+        let parse_error = "x".parse::<i64>().unwrap_err();
+        let cases = [
+            (TransactionError::InvalidWalletAddress("x".to_string()), 2),
+            (TransactionError::ZeroAmount, 3),
+            (TransactionError::NoTransactions("x".to_string()), 4),
+            (TransactionError::NegativeAmount(-1), 5),
+            (TransactionError::NegativeFee(-1), 6),
+            (TransactionError::InvalidMintAddress("x".to_string()), 7),
+            (TransactionError::UnknownTransactionType("x".to_string()), 8),
+            (
+                TransactionError::InsufficientFunds {
+                    wallet: "x".to_string(),
+                    balance: 0,
+                    attempted: 1,
+                },
+                9,
+            ),
+            (TransactionError::BalanceOverflow("x".to_string()), 10),
+            (TransactionError::Io(std::io::Error::other("x")), 11),
+            (TransactionError::Json(json_error), 12),
+            (
+                TransactionError::InvalidCsvRow {
+                    line: 1,
+                    reason: "x".to_string(),
+                },
+                13,
+            ),
+            (TransactionError::MissingField("wallet"), 14),
+            (
+                TransactionError::BelowRentExempt {
+                    wallet: "x".to_string(),
+                    balance: 0,
+                    min: 1,
+                },
+                15,
+            ),
+            (TransactionError::InvalidSolAmount(0.5), 16),
+            (TransactionError::ParseError("x".to_string()), 17),
+            (TransactionError::UnsupportedTransactionType("x".to_string()), 18),
+            (TransactionError::AmountExceedsLimit { amount: 2, max: 1 }, 19),
+            (TransactionError::UnknownCluster("x".to_string()), 20),
+            (TransactionError::InterestOverflow(1), 21),
+            (
+                TransactionError::InvalidTransaction {
+                    index: 0,
+                    source: Box::new(TransactionError::ZeroAmount),
+                },
+                22,
+            ),
+            (TransactionError::Toml(toml_error), 23),
+            (TransactionError::AmountBelowMinimum { amount: 1, min: 2 }, 24),
+            (TransactionError::InvalidPercentage(101), 25),
+            (TransactionError::InvalidDecimalAmount("x".to_string()), 26),
+            (TransactionError::SelfTransfer("x".to_string()), 27),
+            (
+                TransactionError::TooManyTransactions {
+                    wallet: "x".to_string(),
+                    count: 2,
+                    max: 1,
+                },
+                28,
+            ),
+            (TransactionError::Parse(parse_error), 29),
+            (TransactionError::InvalidBucketSize(0), 30),
+            (TransactionError::MisplacedOpeningBalance("x".to_string()), 31),
+            (TransactionError::WalletFrozen("x".to_string()), 32),
+            (
+                TransactionError::WalletMismatch {
+                    wallet: "x".to_string(),
+                    transaction_wallet: "y".to_string(),
+                },
+                33,
+            ),
+            (TransactionError::InvalidFeeMultiplier(-1.0), 34),
+            (
+                TransactionError::ParseRow {
+                    line: 1,
+                    source: "x".parse::<i64>().unwrap_err(),
+                },
+                35,
+            ),
+            (
+                TransactionError::Csv {
+                    line: 2,
+                    source: csv::Reader::from_reader("a\n1,2\n".as_bytes()).records().next().unwrap().unwrap_err(),
+                },
+                36,
+            ),
+            (TransactionError::TimestampOutOfRange(i64::MIN), 37),
+        ];
+        for (error, code) in cases {
+            assert_eq!(error.exit_code(), code, "{}", error);
+        }
+    }
+}
@@ -0,0 +1,139 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crate::error::TransactionError;
+use crate::history::{balance_history, SECONDS_PER_DAY};
+use crate::transaction::{Transaction, TransactionStatus};
+
+/// Serializes wallet balances as a JSON object keyed by address
+///
+/// Keys are sorted so that exporting the same balances twice yields identical output,
+/// keeping diffs between runs clean.
+///
+/// # Arguments
+///
+/// * `balances` - The balance of each wallet, keyed by address
+///
+/// # Returns
+///
+/// * `String` - A compact JSON object such as `{"9jLk...":-5,"ALiC...":100}`, sorted by address
+///
+pub fn export_balances_json(balances: &HashMap<String, i64>) -> String {
+    let sorted: BTreeMap<&str, i64> = balances.iter().map(|(wallet, balance)| (wallet.as_str(), *balance)).collect();
+    // Serializing only fails for non-string map keys or a failing Serialize impl, and
+    // string keys with integer values have neither
+    serde_json::to_string(&sorted).expect("a map of strings to integers always serializes")
+}
+
+/// Renders a printable account statement for a wallet
+///
+/// The statement has a header naming the wallet, one line per native SOL transaction in
+/// input order giving its date (UTC), type, signed change in lamports including any fee,
+/// and the running balance, then a footer with the closing balance. Failed transactions
+/// moved no funds and are left out.
+///
+/// # Arguments
+///
+/// * `wallet` - The address of the wallet to produce a statement for
+/// * `transactions` - A slice of transactions to process
+///
+/// # Returns
+///
+/// * `Ok(String)` - The statement, one line per row, each ending in a newline
+/// * `Err(TransactionError)` - If there's an error processing the transactions
+///
+pub fn render_statement(wallet: &str, transactions: &[Transaction]) -> Result<String, TransactionError> {
+    let mut statement = format!("Statement for {}\n", wallet);
+    statement.push_str(&format!("{:<10}  {:<14}  {:>20}  {:>20}\n", "Date", "Type", "Amount", "Balance"));
+
+    let mut previous = 0i64;
+    for (tx, balance) in balance_history(wallet, transactions)? {
+        if tx.status == TransactionStatus::Failed {
+            continue;
+        }
+        let change = i128::from(balance) - i128::from(previous);
+        statement.push_str(&format!(
+            "{:<10}  {:<14}  {:>+20}  {:>20}\n",
+            format_date(tx.timestamp),
+            tx.transaction_type.to_string(),
+            change,
+            balance
+        ));
+        previous = balance;
+    }
+
+    statement.push_str(&format!("Closing balance: {} lamports\n", previous));
+    Ok(statement)
+}
+
+/// Formats a Unix timestamp as a `YYYY-MM-DD` date in UTC
+fn format_date(timestamp: i64) -> String {
+    // Convert days since the epoch to a civil date, counting in 400-year eras from 0000-03-01
+    let days = timestamp.div_euclid(SECONDS_PER_DAY) + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transaction::TransactionType;
+
+    const ALICE: &str = "ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3";
+    const BOB: &str = "9jLkNAaW9E47LQMHvjohy2uAAyr1331bAxgJKFRU7wF6";
+
+    /// Tests the exact JSON produced for two wallets, sorted by address
+    #[test]
+    fn test_export_balances_json_sorted() {
+        let balances = HashMap::from([(ALICE.to_string(), 100), (BOB.to_string(), -5)]);
+        assert_eq!(
+            export_balances_json(&balances),
+            r#"{"9jLkNAaW9E47LQMHvjohy2uAAyr1331bAxgJKFRU7wF6":-5,"ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3":100}"#
+        );
+    }
+
+    /// Tests the lines of a statement for a small history, without its failed transaction
+    #[test]
+    fn test_render_statement() {
+        let transactions = vec![
+            Transaction::new(TransactionType::Deposit, ALICE, 1_000).unwrap().with_timestamp(1_704_067_200),
+            Transaction::new(TransactionType::Withdrawal, ALICE, 300)
+                .unwrap()
+                .with_fee(5)
+                .unwrap()
+                .with_timestamp(1_706_832_000),
+            Transaction::new(TransactionType::Withdrawal, ALICE, 50)
+                .unwrap()
+                .with_status(TransactionStatus::Failed)
+                .with_timestamp(1_709_251_199),
+            Transaction::new(TransactionType::Transfer { to: ALICE.to_string() }, BOB, 20)
+                .unwrap()
+                .with_timestamp(1_709_251_199),
+        ];
+
+        let statement = render_statement(ALICE, &transactions).unwrap();
+        let lines: Vec<&str> = statement.lines().collect();
+        assert_eq!(lines.len(), 6);
+        assert_eq!(lines[0], format!("Statement for {}", ALICE));
+        assert!(lines[1].starts_with("Date"));
+        assert_eq!(lines[2], format!("2024-01-01  Deposit         {:>20}  {:>20}", "+1000", 1_000));
+        assert_eq!(lines[3], format!("2024-02-02  Withdrawal      {:>20}  {:>20}", "-305", 695));
+        assert_eq!(lines[4], format!("2024-02-29  Transfer        {:>20}  {:>20}", "+20", 715));
+        assert_eq!(lines[5], "Closing balance: 715 lamports");
+    }
+
+    /// Tests dates either side of the epoch and across a leap day
+    #[test]
+    fn test_format_date() {
+        assert_eq!(format_date(0), "1970-01-01");
+        assert_eq!(format_date(-1), "1969-12-31");
+        assert_eq!(format_date(951_782_400), "2000-02-29");
+        assert_eq!(format_date(951_868_800), "2000-03-01");
+    }
+}
@@ -0,0 +1,73 @@
+use std::collections::HashMap;
+use std::mem::{self, Discriminant};
+
+use crate::error::TransactionError;
+use crate::transaction::{validate_fee, TransactionType};
+
+/// A fixed fee charged per transaction, depending on the kind of transaction
+///
+/// Fees are keyed by kind only, so a transfer fee applies whatever the destination.
+/// Kinds without an entry cost nothing.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FeeSchedule {
+    /// The fee for each kind of transaction, in lamports
+    fees: HashMap<Discriminant<TransactionType>, i64>,
+}
+
+impl FeeSchedule {
+    /// Creates an empty schedule in which every transaction is free
+    pub fn new() -> FeeSchedule {
+        FeeSchedule::default()
+    }
+
+    /// Sets the fee charged for every transaction of the same kind as `transaction_type`
+    ///
+    /// # Arguments
+    ///
+    /// * `transaction_type` - The kind of transaction; a transfer's destination is ignored
+    /// * `fee` - The fee, in lamports (must not be negative)
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(FeeSchedule)` - The schedule with the fee set
+    /// * `Err(TransactionError)` - If the fee is negative
+    ///
+    pub fn with_fee(mut self, transaction_type: &TransactionType, fee: i64) -> Result<FeeSchedule, TransactionError> {
+        validate_fee(fee)?;
+        self.fees.insert(mem::discriminant(transaction_type), fee);
+        Ok(self)
+    }
+
+    /// Returns the fee scheduled for transactions of the same kind as `transaction_type`, or zero
+    pub fn fee_for(&self, transaction_type: &TransactionType) -> i64 {
+        self.fees.get(&mem::discriminant(transaction_type)).copied().unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests that transfer fees ignore the destination and missing entries are free
+    #[test]
+    fn test_fee_for() {
+        let schedule = FeeSchedule::new()
+            .with_fee(&TransactionType::Transfer { to: String::new() }, 7)
+            .unwrap();
+
+        let transfer = TransactionType::Transfer {
+            to: "9jLkNAaW9E47LQMHvjohy2uAAyr1331bAxgJKFRU7wF6".to_string(),
+        };
+        assert_eq!(schedule.fee_for(&transfer), 7);
+        assert_eq!(schedule.fee_for(&TransactionType::Deposit), 0);
+    }
+
+    /// Tests that a negative scheduled fee is rejected
+    #[test]
+    fn test_negative_scheduled_fee() {
+        assert!(matches!(
+            FeeSchedule::new().with_fee(&TransactionType::Deposit, -1),
+            Err(TransactionError::NegativeFee(-1))
+        ));
+    }
+}
@@ -0,0 +1,665 @@
+use crate::address::{normalize_wallet_address, validate_address};
+use crate::balance::{apply_transaction, indexed_for_wallet, locate_error, OpeningBalanceGuard};
+use crate::error::TransactionError;
+use crate::transaction::Transaction;
+
+/// Calculates the balance of a wallet as of a point in time
+///
+/// Only native SOL transactions with a timestamp at or before `as_of` are counted,
+/// and they are applied in timestamp order regardless of their order in the slice.
+///
+/// # Arguments
+///
+/// * `wallet_address` - The address of the wallet to calculate the balance for
+/// * `transactions` - A slice of transactions to process
+/// * `as_of` - The cutoff time, in Unix seconds (inclusive)
+///
+/// # Returns
+///
+/// * `Ok(i64)` - The balance at the given time
+/// * `Err(TransactionError)` - If there's an error processing the transactions
+///
+pub fn balance_at(wallet_address: &str, transactions: &[Transaction], as_of: i64) -> Result<i64, TransactionError> {
+    validate_address(wallet_address)?;
+    if transactions.is_empty() {
+        return Err(TransactionError::NoTransactions(wallet_address.to_string()));
+    }
+
+    let mut history: Vec<(usize, &Transaction)> = indexed_for_wallet(wallet_address, transactions)
+        .filter(|(_, tx)| tx.mint.is_none())
+        .filter(|(_, tx)| tx.timestamp <= as_of)
+        .collect();
+    history.sort_by_key(|(_, tx)| tx.timestamp);
+
+    let mut opening = OpeningBalanceGuard::default();
+    history.into_iter().try_fold(0i64, |acc, (index, tx)| {
+        let locate = move |e| locate_error(index, e);
+        opening.record(tx, wallet_address).map_err(locate)?;
+        apply_transaction(acc, tx, wallet_address).map_err(locate)
+    })
+}
+
+/// Lists each of a wallet's transactions alongside the running balance immediately after it
+///
+/// Transactions are reported in input order, which makes the result suitable for
+/// reconciling against an external statement.
+///
+/// # Arguments
+///
+/// * `wallet_address` - The address of the wallet to trace
+/// * `transactions` - A slice of transactions to process
+///
+/// # Returns
+///
+/// * `Ok(Vec<(Transaction, i64)>)` - Each matching native SOL transaction with the balance after it
+/// * `Err(TransactionError)` - If there's an error processing the transactions
+///
+pub fn balance_history(
+    wallet_address: &str,
+    transactions: &[Transaction],
+) -> Result<Vec<(Transaction, i64)>, TransactionError> {
+    validate_address(wallet_address)?;
+    if transactions.is_empty() {
+        return Err(TransactionError::NoTransactions(wallet_address.to_string()));
+    }
+
+    let mut balance = 0i64;
+    let mut history = Vec::new();
+    let mut opening = OpeningBalanceGuard::default();
+    for (index, tx) in indexed_for_wallet(wallet_address, transactions).filter(|(_, tx)| tx.mint.is_none()) {
+        let locate = move |e| locate_error(index, e);
+        opening.record(tx, wallet_address).map_err(locate)?;
+        balance = apply_transaction(balance, tx, wallet_address).map_err(locate)?;
+        history.push((tx.clone(), balance));
+    }
+    Ok(history)
+}
+
+/// Calculates a wallet's balance as if its most recent transaction had not happened
+///
+/// The most recent transaction is the one with the latest timestamp; among transactions
+/// sharing that timestamp, the last in the slice is undone.
+///
+/// # Arguments
+///
+/// * `wallet` - The address of the wallet to roll back
+/// * `transactions` - A slice of transactions to process
+///
+/// # Returns
+///
+/// * `Ok(i64)` - The balance without the most recent native SOL transaction
+/// * `Err(TransactionError)` - `NoTransactions` if no native SOL transaction involves the
+///   wallet, or any error from processing the rest
+///
+pub fn rollback_last(wallet: &str, transactions: &[Transaction]) -> Result<i64, TransactionError> {
+    validate_address(wallet)?;
+    let matching: Vec<(usize, &Transaction)> = indexed_for_wallet(wallet, transactions)
+        .filter(|(_, tx)| tx.mint.is_none())
+        .collect();
+
+    // max_by_key returns the last of several equal maxima
+    let (last, _) = matching
+        .iter()
+        .max_by_key(|(_, tx)| tx.timestamp)
+        .ok_or_else(|| TransactionError::NoTransactions(wallet.to_string()))?;
+
+    // The undone transaction still counts towards where an opening balance may fall
+    let mut opening = OpeningBalanceGuard::default();
+    matching.iter().try_fold(0i64, |acc, &(index, tx)| {
+        let locate = move |e| locate_error(index, e);
+        opening.record(tx, wallet).map_err(locate)?;
+        if index == *last {
+            return Ok(acc);
+        }
+        apply_transaction(acc, tx, wallet).map_err(locate)
+    })
+}
+
+/// Finds the first transaction that pushes a wallet's running balance below zero
+///
+/// Transactions are applied in slice order; SPL token transactions and those for other
+/// wallets are skipped.
+///
+/// # Arguments
+///
+/// * `wallet` - The address of the wallet to check
+/// * `transactions` - A slice of transactions to process
+///
+/// # Returns
+///
+/// * `Ok(Some((usize, i64)))` - The slice index of the offending transaction and the negative balance after it
+/// * `Ok(None)` - If the running balance never goes negative
+/// * `Err(TransactionError)` - If a transaction before any overdraft cannot be applied, so the
+///   history cannot be checked
+///
+pub fn first_overdraft(wallet: &str, transactions: &[Transaction]) -> Result<Option<(usize, i64)>, TransactionError> {
+    let mut balance = 0i64;
+    let mut opening = OpeningBalanceGuard::default();
+    for (index, tx) in indexed_for_wallet(wallet, transactions).filter(|(_, tx)| tx.mint.is_none()) {
+        let locate = move |e| locate_error(index, e);
+        opening.record(tx, wallet).map_err(locate)?;
+        balance = apply_transaction(balance, tx, wallet).map_err(locate)?;
+        if balance < 0 {
+            return Ok(Some((index, balance)));
+        }
+    }
+    Ok(None)
+}
+
+/// Seconds in a UTC day, used to bucket timestamps by day
+pub(crate) const SECONDS_PER_DAY: i64 = 86_400;
+
+/// Summarizes a wallet's native SOL balance at the close of each day it was active
+///
+/// Transactions are applied in timestamp order and bucketed into UTC days by integer
+/// division of their timestamps. Each day's closing balance carries over into the next
+/// active day. SPL token transactions and those for other wallets are skipped.
+///
+/// # Arguments
+///
+/// * `wallet` - The address of the wallet to chart
+/// * `transactions` - A slice of transactions to process
+///
+/// # Returns
+///
+/// * `Ok(Vec<(i64, i64)>)` - `(day_start, closing_balance)` pairs for each active day, in order,
+///   where `day_start` is in Unix seconds
+/// * `Err(TransactionError)` - If the address is invalid, or any transaction cannot be applied or
+///   has a timestamp too close to `i64::MIN` to bucket, rather than a partial timeline
+///
+pub fn daily_balances(wallet: &str, transactions: &[Transaction]) -> Result<Vec<(i64, i64)>, TransactionError> {
+    let wallet = normalize_wallet_address(wallet)?;
+    let wallet = wallet.as_str();
+    let mut history: Vec<(usize, &Transaction)> = indexed_for_wallet(wallet, transactions)
+        .filter(|(_, tx)| tx.mint.is_none())
+        .collect();
+    history.sort_by_key(|(_, tx)| tx.timestamp);
+
+    let mut balance = 0i64;
+    let mut days: Vec<(i64, i64)> = Vec::new();
+    let mut opening = OpeningBalanceGuard::default();
+    for (index, tx) in history {
+        let locate = move |e| locate_error(index, e);
+        opening.record(tx, wallet).map_err(locate)?;
+        balance = apply_transaction(balance, tx, wallet).map_err(locate)?;
+
+        let day_start = tx
+            .timestamp
+            .div_euclid(SECONDS_PER_DAY)
+            .checked_mul(SECONDS_PER_DAY)
+            .ok_or_else(|| locate(TransactionError::TimestampOutOfRange(tx.timestamp)))?;
+        match days.last_mut() {
+            Some((day, closing)) if *day == day_start => *closing = balance,
+            _ => days.push((day_start, balance)),
+        }
+    }
+    Ok(days)
+}
+
+/// Iterator adaptor yielding each of a wallet's transactions with the running balance after it
+///
+/// This is a lazy [`balance_history`]: transactions are pulled from the wrapped iterator one
+/// at a time, so rows can be rendered as they stream in. Transactions for other wallets
+/// and SPL token transactions are skipped. The first invalid transaction, such as one with
+/// a zero amount, is yielded as an error and ends the iteration.
+#[derive(Debug)]
+pub struct RunningBalance<I> {
+    /// The address of the wallet being traced
+    wallet_address: String,
+    /// The transactions still to be processed
+    transactions: I,
+    /// The position in the wrapped iterator of the next transaction, reported with errors
+    position: usize,
+    /// The balance after the last transaction yielded
+    balance: i64,
+    /// The wallet's transactions so far, so that an opening balance can only come first
+    opening: OpeningBalanceGuard,
+    /// Whether an error has been yielded, ending the iteration
+    failed: bool,
+}
+
+impl<I: Iterator<Item = Transaction>> RunningBalance<I> {
+    /// Wraps a stream of transactions to trace a wallet's running balance
+    ///
+    /// # Arguments
+    ///
+    /// * `wallet_address` - The address of the wallet to trace
+    /// * `transactions` - Any iterator (or collection) yielding transactions
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(RunningBalance)` - The adaptor, starting from a balance of zero
+    /// * `Err(TransactionError)` - If the wallet address is invalid
+    ///
+    pub fn new(
+        wallet_address: &str,
+        transactions: impl IntoIterator<IntoIter = I>,
+    ) -> Result<RunningBalance<I>, TransactionError> {
+        validate_address(wallet_address)?;
+        Ok(RunningBalance {
+            wallet_address: wallet_address.to_string(),
+            transactions: transactions.into_iter(),
+            position: 0,
+            balance: 0,
+            opening: OpeningBalanceGuard::default(),
+            failed: false,
+        })
+    }
+}
+
+impl<I: Iterator<Item = Transaction>> Iterator for RunningBalance<I> {
+    type Item = Result<(Transaction, i64), TransactionError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        let (index, tx) = loop {
+            let tx = self.transactions.next()?;
+            let index = self.position;
+            self.position += 1;
+            if tx.involves(&self.wallet_address) && tx.mint.is_none() {
+                break (index, tx);
+            }
+        };
+
+        let applied = self
+            .opening
+            .record(&tx, &self.wallet_address)
+            .and_then(|()| apply_transaction(self.balance, &tx, &self.wallet_address));
+        match applied.map_err(|e| locate_error(index, e)) {
+            Ok(balance) => {
+                self.balance = balance;
+                Some(Ok((tx, balance)))
+            }
+            Err(e) => {
+                self.failed = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+/// Selects the transactions whose timestamp falls within `start..=end`
+///
+/// An inverted range (`start > end`) contains no timestamps and yields an empty vec.
+///
+/// # Arguments
+///
+/// * `transactions` - A slice of transactions to filter
+/// * `start` - The start of the window, in Unix seconds (inclusive)
+/// * `end` - The end of the window, in Unix seconds (inclusive)
+///
+/// # Returns
+///
+/// * `Vec<Transaction>` - The transactions within the window, in input order
+///
+pub fn filter_by_range(transactions: &[Transaction], start: i64, end: i64) -> Vec<Transaction> {
+    transactions
+        .iter()
+        .filter(|tx| (start..=end).contains(&tx.timestamp))
+        .cloned()
+        .collect()
+}
+
+/// Sorts transactions into canonical order by timestamp, then signature
+///
+/// The sort is stable: transactions with the same timestamp and signature (including
+/// unsigned ones, whose signature is empty) keep their relative input order.
+///
+/// # Arguments
+///
+/// * `transactions` - The transactions to sort in place
+///
+pub fn sort_transactions(transactions: &mut [Transaction]) {
+    transactions.sort_by(|a, b| (a.timestamp, &a.signature).cmp(&(b.timestamp, &b.signature)));
+}
+
+/// Selects the transactions whose memo contains `needle`, ignoring case
+///
+/// Transactions without a memo never match, not even an empty `needle`.
+///
+/// # Arguments
+///
+/// * `transactions` - A slice of transactions to filter
+/// * `needle` - The text to search memos for
+///
+/// # Returns
+///
+/// * `Vec<Transaction>` - The transactions whose memo matches, in input order
+///
+pub fn filter_by_memo_substring(transactions: &[Transaction], needle: &str) -> Vec<Transaction> {
+    let needle = needle.to_lowercase();
+    transactions
+        .iter()
+        .filter(|tx| tx.memo.as_ref().is_some_and(|memo| memo.to_lowercase().contains(&needle)))
+        .cloned()
+        .collect()
+}
+
+/// Removes dust, the transactions too small to matter to an analysis
+///
+/// # Arguments
+///
+/// * `transactions` - A slice of transactions to filter
+/// * `threshold` - The smallest amount kept; anything below it is dust
+///
+/// # Returns
+///
+/// * `Vec<Transaction>` - The transactions whose amount is at least `threshold`, in input order
+///
+pub fn filter_dust(transactions: &[Transaction], threshold: i64) -> Vec<Transaction> {
+    transactions.iter().filter(|tx| tx.amount >= threshold).cloned().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transaction::TransactionType;
+
+    const ALICE: &str = "ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3";
+    const BOB: &str = "9jLkNAaW9E47LQMHvjohy2uAAyr1331bAxgJKFRU7wF6";
+
+    /// Tests the balance at a point between timestamped transactions given out of order
+    #[test]
+    fn test_balance_at_midpoint() {
+        let transactions = vec![
+            Transaction::new(TransactionType::Deposit, ALICE, 50).unwrap().with_timestamp(300),
+            Transaction::new(TransactionType::Deposit, ALICE, 100).unwrap().with_timestamp(100),
+            Transaction::new(TransactionType::Withdrawal, ALICE, 30).unwrap().with_timestamp(200),
+        ];
+
+        assert_eq!(balance_at(ALICE, &transactions, 250).unwrap(), 70);
+        assert_eq!(balance_at(ALICE, &transactions, 200).unwrap(), 70);
+        assert_eq!(balance_at(ALICE, &transactions, 99).unwrap(), 0);
+        assert_eq!(balance_at(ALICE, &transactions, 300).unwrap(), 120);
+    }
+
+    /// Tests the sequence of running balances for a three-transaction history
+    #[test]
+    fn test_balance_history() {
+        let transactions = vec![
+            Transaction::new(TransactionType::Deposit, ALICE, 100).unwrap(),
+            Transaction::new(TransactionType::Deposit, BOB, 1_000).unwrap(),
+            Transaction::new(TransactionType::Withdrawal, ALICE, 30).unwrap(),
+            Transaction::new(TransactionType::Deposit, ALICE, 5).unwrap(),
+        ];
+
+        let history = balance_history(ALICE, &transactions).unwrap();
+        let balances: Vec<i64> = history.iter().map(|(_, balance)| *balance).collect();
+        assert_eq!(balances, vec![100, 70, 75]);
+        assert_eq!(history[1].0, transactions[2]);
+    }
+
+    /// Tests that both ends of the range are inclusive
+    #[test]
+    fn test_filter_by_range_inclusive() {
+        let transactions: Vec<Transaction> = [99, 100, 150, 200, 201]
+            .into_iter()
+            .map(|t| Transaction::new(TransactionType::Deposit, ALICE, 1).unwrap().with_timestamp(t))
+            .collect();
+
+        let timestamps: Vec<i64> = filter_by_range(&transactions, 100, 200)
+            .iter()
+            .map(Transaction::timestamp)
+            .collect();
+        assert_eq!(timestamps, vec![100, 150, 200]);
+    }
+
+    /// Tests that an inverted range yields no transactions
+    #[test]
+    fn test_filter_by_inverted_range() {
+        let transactions = vec![Transaction::new(TransactionType::Deposit, ALICE, 1).unwrap().with_timestamp(150)];
+        assert!(filter_by_range(&transactions, 200, 100).is_empty());
+    }
+
+    /// Tests a case-insensitive memo search over a batch where only some memos match
+    #[test]
+    fn test_filter_by_memo_substring() {
+        let transactions = vec![
+            Transaction::new(TransactionType::Deposit, ALICE, 1).unwrap().with_memo("Rent for March"),
+            Transaction::new(TransactionType::Deposit, ALICE, 2).unwrap().with_memo("groceries"),
+            Transaction::new(TransactionType::Deposit, ALICE, 3).unwrap(),
+            Transaction::new(TransactionType::Withdrawal, ALICE, 4).unwrap().with_memo("RENT for April"),
+        ];
+
+        let amounts: Vec<i64> = filter_by_memo_substring(&transactions, "rent")
+            .iter()
+            .map(Transaction::amount)
+            .collect();
+        assert_eq!(amounts, vec![1, 4]);
+        assert_eq!(filter_by_memo_substring(&transactions, "").len(), 3);
+    }
+
+    /// Tests that only transactions below the dust threshold are removed
+    #[test]
+    fn test_filter_dust() {
+        let transactions = vec![
+            Transaction::new(TransactionType::Deposit, ALICE, 1_000).unwrap(),
+            Transaction::new(TransactionType::Deposit, ALICE, 3).unwrap(),
+            Transaction::new(TransactionType::Withdrawal, BOB, 10).unwrap(),
+            Transaction::new(TransactionType::Deposit, BOB, 9).unwrap(),
+        ];
+
+        let amounts: Vec<i64> = filter_dust(&transactions, 10).iter().map(Transaction::amount).collect();
+        assert_eq!(amounts, vec![1_000, 10]);
+    }
+
+    /// Tests that a shared timestamp is ordered by signature, and unsigned ties keep input order
+    #[test]
+    fn test_sort_transactions_shared_timestamp() {
+        let mut transactions = vec![
+            Transaction::new(TransactionType::Deposit, ALICE, 1).unwrap().with_timestamp(200),
+            Transaction::new(TransactionType::Deposit, ALICE, 2).unwrap().with_timestamp(100).with_signature("b"),
+            Transaction::new(TransactionType::Deposit, ALICE, 3).unwrap().with_timestamp(100),
+            Transaction::new(TransactionType::Deposit, ALICE, 4).unwrap().with_timestamp(100).with_signature("a"),
+            Transaction::new(TransactionType::Deposit, ALICE, 5).unwrap().with_timestamp(100),
+        ];
+
+        sort_transactions(&mut transactions);
+        let amounts: Vec<i64> = transactions.iter().map(Transaction::amount).collect();
+        assert_eq!(amounts, vec![3, 5, 4, 2, 1]);
+    }
+
+    /// Tests that the lazy running balance matches the eager balance history
+    #[test]
+    fn test_running_balance_matches_history() {
+        let transactions = vec![
+            Transaction::new(TransactionType::Deposit, ALICE, 100).unwrap(),
+            Transaction::new(TransactionType::Deposit, BOB, 1_000).unwrap(),
+            Transaction::new(TransactionType::Transfer { to: ALICE.to_string() }, BOB, 40).unwrap(),
+            Transaction::new(TransactionType::Withdrawal, ALICE, 30).unwrap(),
+        ];
+
+        let running: Vec<(Transaction, i64)> = RunningBalance::new(ALICE, transactions.clone())
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(running, balance_history(ALICE, &transactions).unwrap());
+    }
+
+    /// Tests that a zero amount is surfaced as an error and ends the iteration
+    #[test]
+    fn test_running_balance_stops_on_zero_amount() {
+        let mut zero = Transaction::new(TransactionType::Deposit, ALICE, 1).unwrap();
+        zero.amount = 0;
+        let transactions = vec![
+            Transaction::new(TransactionType::Deposit, ALICE, 100).unwrap(),
+            zero,
+            Transaction::new(TransactionType::Deposit, ALICE, 5).unwrap(),
+        ];
+
+        let mut running = RunningBalance::new(ALICE, transactions).unwrap();
+        assert_eq!(running.next().unwrap().unwrap().1, 100);
+        assert!(matches!(running.next(), Some(Err(TransactionError::InvalidTransaction { index: 1, .. }))));
+        assert!(running.next().is_none());
+    }
+
+    /// Tests undoing the most recent transaction, which is not the last in the slice
+    #[test]
+    fn test_rollback_last() {
+        let transactions = vec![
+            Transaction::new(TransactionType::Deposit, ALICE, 100).unwrap().with_timestamp(100),
+            Transaction::new(TransactionType::Withdrawal, ALICE, 30).unwrap().with_timestamp(300),
+            Transaction::new(TransactionType::Deposit, BOB, 1_000).unwrap().with_timestamp(400),
+            Transaction::new(TransactionType::Deposit, ALICE, 5).unwrap().with_timestamp(200),
+        ];
+
+        assert_eq!(rollback_last(ALICE, &transactions).unwrap(), 105);
+        assert!(matches!(
+            rollback_last(ALICE, &transactions[2..3]),
+            Err(TransactionError::NoTransactions(_))
+        ));
+    }
+
+    /// Tests that the first transaction taking the balance negative is reported
+    #[test]
+    fn test_first_overdraft() {
+        let transactions = vec![
+            Transaction::new(TransactionType::Deposit, ALICE, 100).unwrap(),
+            Transaction::new(TransactionType::Withdrawal, ALICE, 60).unwrap(),
+            Transaction::new(TransactionType::Deposit, BOB, 500).unwrap(),
+            Transaction::new(TransactionType::Withdrawal, ALICE, 70).unwrap(),
+            Transaction::new(TransactionType::Withdrawal, ALICE, 10).unwrap(),
+        ];
+
+        assert_eq!(first_overdraft(ALICE, &transactions).unwrap(), Some((3, -30)));
+        assert_eq!(first_overdraft(ALICE, &transactions[..3]).unwrap(), None);
+        assert_eq!(first_overdraft(BOB, &transactions).unwrap(), None);
+    }
+
+    /// Tests that an invalid transaction before an overdraft is reported rather than hidden
+    #[test]
+    fn test_first_overdraft_invalid_transaction() {
+        let mut zero = Transaction::new(TransactionType::Deposit, ALICE, 1).unwrap();
+        zero.amount = 0;
+        let transactions = vec![
+            Transaction::new(TransactionType::Deposit, ALICE, 100).unwrap(),
+            zero,
+            Transaction::new(TransactionType::Withdrawal, ALICE, 150).unwrap(),
+        ];
+
+        assert!(matches!(
+            first_overdraft(ALICE, &transactions),
+            Err(TransactionError::InvalidTransaction { index: 1, .. })
+        ));
+    }
+
+    /// Tests that each active day reports its closing balance, carried over between days
+    #[test]
+    fn test_daily_balances() {
+        let day = 86_400;
+        let transactions = vec![
+            Transaction::new(TransactionType::Deposit, ALICE, 20).unwrap().with_timestamp(day + 60),
+            Transaction::new(TransactionType::Deposit, ALICE, 100).unwrap().with_timestamp(day - 1),
+            Transaction::new(TransactionType::Withdrawal, ALICE, 30).unwrap().with_timestamp(10),
+            Transaction::new(TransactionType::Deposit, BOB, 500).unwrap().with_timestamp(20),
+            Transaction::new(TransactionType::Withdrawal, ALICE, 5).unwrap().with_timestamp(day + 3_600),
+        ];
+
+        assert_eq!(daily_balances(ALICE, &transactions).unwrap(), vec![(0, 70), (day, 85)]);
+    }
+
+    /// Tests that an invalid transaction fails the whole timeline instead of truncating it
+    #[test]
+    fn test_daily_balances_invalid_transaction() {
+        let mut zero = Transaction::new(TransactionType::Deposit, ALICE, 1).unwrap().with_timestamp(100);
+        zero.amount = 0;
+        let transactions = vec![
+            Transaction::new(TransactionType::Deposit, ALICE, 100).unwrap().with_timestamp(10),
+            Transaction::new(TransactionType::Deposit, ALICE, 50).unwrap().with_timestamp(86_400),
+            zero,
+        ];
+
+        assert!(matches!(
+            daily_balances(ALICE, &transactions),
+            Err(TransactionError::InvalidTransaction { index: 2, .. })
+        ));
+    }
+
+    /// Tests that a timestamp whose day would start before `i64::MIN` is rejected rather than overflowing
+    #[test]
+    fn test_daily_balances_timestamp_out_of_range() {
+        let transactions = vec![
+            Transaction::new(TransactionType::Deposit, ALICE, 100).unwrap().with_timestamp(10),
+            Transaction::new(TransactionType::Deposit, ALICE, 50).unwrap().with_timestamp(i64::MIN),
+        ];
+
+        assert!(matches!(
+            daily_balances(ALICE, &transactions),
+            Err(TransactionError::InvalidTransaction { index: 1, source })
+                if matches!(*source, TransactionError::TimestampOutOfRange(i64::MIN))
+        ));
+    }
+
+    /// Tests that the wallet address is trimmed and validated before charting
+    #[test]
+    fn test_daily_balances_normalizes_wallet() {
+        let transactions = vec![Transaction::new(TransactionType::Deposit, ALICE, 100).unwrap().with_timestamp(10)];
+
+        assert_eq!(daily_balances(&format!("  {}\n", ALICE), &transactions).unwrap(), vec![(0, 100)]);
+        assert!(matches!(
+            daily_balances("not-a-wallet", &transactions),
+            Err(TransactionError::InvalidWalletAddress(_))
+        ));
+    }
+
+    /// Returns whether `result` is a misplaced opening balance for Alice at slice index `index`
+    fn is_misplaced_opening<T>(result: Result<T, TransactionError>, index: usize) -> bool {
+        matches!(
+            result,
+            Err(TransactionError::InvalidTransaction { index: found, source })
+                if found == index && matches!(&*source, TransactionError::MisplacedOpeningBalance(wallet) if wallet == ALICE)
+        )
+    }
+
+    /// Returns a history in which Alice's opening balance follows a deposit
+    fn misplaced_opening_history() -> Vec<Transaction> {
+        vec![
+            Transaction::new(TransactionType::Deposit, ALICE, 100).unwrap().with_timestamp(100),
+            Transaction::new(TransactionType::Deposit, BOB, 50).unwrap().with_timestamp(150),
+            Transaction::new(TransactionType::OpeningBalance, ALICE, 1_000).unwrap().with_timestamp(200),
+            Transaction::new(TransactionType::Withdrawal, ALICE, 10).unwrap().with_timestamp(300),
+        ]
+    }
+
+    /// Tests that balance_at rejects an opening balance after other activity
+    #[test]
+    fn test_balance_at_misplaced_opening_balance() {
+        assert!(is_misplaced_opening(balance_at(ALICE, &misplaced_opening_history(), 300), 2));
+    }
+
+    /// Tests that balance_history rejects an opening balance after other activity
+    #[test]
+    fn test_balance_history_misplaced_opening_balance() {
+        assert!(is_misplaced_opening(balance_history(ALICE, &misplaced_opening_history()), 2));
+    }
+
+    /// Tests that rollback_last rejects an opening balance after other activity
+    #[test]
+    fn test_rollback_last_misplaced_opening_balance() {
+        assert!(is_misplaced_opening(rollback_last(ALICE, &misplaced_opening_history()), 2));
+    }
+
+    /// Tests that first_overdraft rejects an opening balance after other activity
+    #[test]
+    fn test_first_overdraft_misplaced_opening_balance() {
+        assert!(is_misplaced_opening(first_overdraft(ALICE, &misplaced_opening_history()), 2));
+    }
+
+    /// Tests that daily_balances rejects an opening balance after other activity
+    #[test]
+    fn test_daily_balances_misplaced_opening_balance() {
+        assert!(is_misplaced_opening(daily_balances(ALICE, &misplaced_opening_history()), 2));
+    }
+
+    /// Tests that RunningBalance yields an error for an opening balance after other activity
+    #[test]
+    fn test_running_balance_misplaced_opening_balance() {
+        let mut running = RunningBalance::new(ALICE, misplaced_opening_history()).unwrap();
+
+        assert_eq!(running.next().unwrap().unwrap().1, 100);
+        assert!(is_misplaced_opening(running.next().unwrap(), 2));
+        assert!(running.next().is_none());
+    }
+}
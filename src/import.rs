@@ -0,0 +1,402 @@
+use std::fs;
+use std::io::{BufRead, Read};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::balance::locate_error;
+use crate::error::TransactionError;
+use crate::transaction::{parse_amount, Transaction, TransactionType};
+
+/// A single row of a `type,wallet,amount` CSV export
+#[derive(Debug, Deserialize)]
+struct CsvRecord {
+    /// The transaction type name, parsed case-insensitively
+    #[serde(rename = "type")]
+    transaction_type: String,
+    /// The wallet address of the transaction
+    wallet: String,
+    /// The amount of the transaction, parsed separately so a malformed one keeps its parse error
+    amount: String,
+}
+
+/// A trade from a common exchange export format
+///
+/// Buying the base asset (SOL) credits the wallet with `base_amount` lamports; selling it
+/// debits them. The quote side of the trade is kept for reference only.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExchangeRecord {
+    /// The wallet the base asset was credited to or debited from
+    pub wallet: String,
+    /// Which way the trade went, `buy` or `sell` (case-insensitive)
+    pub side: String,
+    /// The amount of SOL traded, in lamports
+    pub base_amount: i64,
+    /// The amount of the quote currency paid or received, in its smallest unit
+    pub quote_amount: i64,
+}
+
+impl TryFrom<ExchangeRecord> for Transaction {
+    type Error = TransactionError;
+
+    /// Converts a buy into a deposit and a sell into a withdrawal of the base amount
+    fn try_from(record: ExchangeRecord) -> Result<Transaction, TransactionError> {
+        let transaction_type = match record.side.to_ascii_lowercase().as_str() {
+            "buy" => TransactionType::Deposit,
+            "sell" => TransactionType::Withdrawal,
+            _ => return Err(TransactionError::UnknownTransactionType(record.side)),
+        };
+        Transaction::new(transaction_type, record.wallet, record.base_amount)
+    }
+}
+
+/// Loads a transaction history from a JSON file containing an array of transactions
+///
+/// Every transaction is validated after parsing, so the returned transactions
+/// satisfy the same rules as those built with [`Transaction::new`].
+///
+/// # Arguments
+///
+/// * `path` - The path of the JSON file to read
+///
+/// # Returns
+///
+/// * `Ok(Vec<Transaction>)` - The parsed transactions, in file order
+/// * `Err(TransactionError)` - If the file cannot be read, is malformed, or contains an invalid transaction
+///
+pub fn load_transactions_from_json(path: &Path) -> Result<Vec<Transaction>, TransactionError> {
+    parse_transactions_json(&fs::read_to_string(path)?)
+}
+
+/// Parses a transaction history from a JSON array of transactions held in memory
+///
+/// Every transaction is validated after parsing, as by [`load_transactions_from_json`].
+///
+/// # Arguments
+///
+/// * `json` - The JSON text to parse
+///
+/// # Returns
+///
+/// * `Ok(Vec<Transaction>)` - The parsed transactions, in array order
+/// * `Err(TransactionError)` - If the JSON is malformed or contains an invalid transaction
+///
+pub fn parse_transactions_json(json: &str) -> Result<Vec<Transaction>, TransactionError> {
+    let transactions: Vec<Transaction> = serde_json::from_str(json)?;
+    for (index, tx) in transactions.iter().enumerate() {
+        tx.validate().map_err(|e| locate_error(index, e))?;
+    }
+    Ok(transactions)
+}
+
+/// Loads a transaction history from newline-delimited JSON, one transaction object per line
+///
+/// Blank lines are skipped, and every transaction is validated after parsing.
+///
+/// # Arguments
+///
+/// * `reader` - The source of the JSON lines, such as stdin
+///
+/// # Returns
+///
+/// * `Ok(Vec<Transaction>)` - The parsed transactions, in line order
+/// * `Err(TransactionError)` - If a line cannot be read, is malformed, or holds an invalid transaction
+///
+pub fn load_transactions_from_ndjson(reader: impl BufRead) -> Result<Vec<Transaction>, TransactionError> {
+    let mut transactions = Vec::new();
+    for line in reader.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let tx: Transaction = serde_json::from_str(&line)?;
+        tx.validate().map_err(|e| locate_error(transactions.len(), e))?;
+        transactions.push(tx);
+    }
+    Ok(transactions)
+}
+
+/// Loads a transaction history from CSV data with a `type,wallet,amount` header
+///
+/// Amounts may use thousands separators, as accepted by [`parse_amount`](crate::parse_amount).
+///
+/// # Arguments
+///
+/// * `reader` - The source of the CSV data
+///
+/// # Returns
+///
+/// * `Ok(Vec<Transaction>)` - The parsed transactions, in row order
+/// * `Err(TransactionError)` - `Csv` if a row cannot be read or has missing fields, `ParseRow` if an
+///   amount is not an integer, or `InvalidCsvRow` for any other invalid row; each identifies the line number
+///
+pub fn load_transactions_from_csv(reader: impl Read) -> Result<Vec<Transaction>, TransactionError> {
+    let mut csv_reader = csv::ReaderBuilder::new().trim(csv::Trim::All).from_reader(reader);
+    let headers = csv_reader.headers().map_err(csv_row_error)?.clone();
+
+    let mut transactions = Vec::new();
+    for record in csv_reader.records() {
+        let record = record.map_err(csv_row_error)?;
+        let line = record.position().map_or(0, |position| position.line());
+        let row_error = |e: &dyn std::fmt::Display| TransactionError::InvalidCsvRow {
+            line,
+            reason: e.to_string(),
+        };
+
+        let row: CsvRecord = record
+            .deserialize(Some(&headers))
+            .map_err(|source| TransactionError::Csv { line, source })?;
+        let transaction_type = row.transaction_type.parse().map_err(|e| row_error(&e))?;
+        let amount = parse_amount(&row.amount).map_err(|e| match e {
+            TransactionError::Parse(source) => TransactionError::ParseRow { line, source },
+            e => row_error(&e),
+        })?;
+        let tx = Transaction::new(transaction_type, row.wallet, amount).map_err(|e| row_error(&e))?;
+        transactions.push(tx);
+    }
+    Ok(transactions)
+}
+
+/// Checks every transaction, collecting all failures instead of stopping at the first
+///
+/// Each transaction's addresses, mint, fee, and amount are validated; a transaction
+/// with several problems is reported once, for the first problem found.
+///
+/// # Arguments
+///
+/// * `transactions` - A slice of transactions to validate
+///
+/// # Returns
+///
+/// * `Vec<(usize, TransactionError)>` - The index and error of each invalid transaction,
+///   in input order; empty if every transaction is valid
+///
+pub fn validate_all(transactions: &[Transaction]) -> Vec<(usize, TransactionError)> {
+    transactions
+        .iter()
+        .enumerate()
+        .filter_map(|(index, tx)| tx.validate().err().map(|e| (index, e)))
+        .collect()
+}
+
+/// Splits transactions into those that pass validation and those that fail it
+///
+/// Uses the same checks as `validate_all`, but keeps ownership of each transaction so
+/// a caller can process the valid ones and report or repair the rest.
+///
+/// # Arguments
+///
+/// * `transactions` - The transactions to validate
+///
+/// # Returns
+///
+/// * `(Vec<Transaction>, Vec<(Transaction, TransactionError)>)` - The valid transactions,
+///   and each invalid transaction with its first error, both in input order
+///
+pub fn partition_valid(transactions: Vec<Transaction>) -> (Vec<Transaction>, Vec<(Transaction, TransactionError)>) {
+    let mut valid = Vec::new();
+    let mut invalid = Vec::new();
+    for tx in transactions {
+        match tx.validate() {
+            Ok(()) => valid.push(tx),
+            Err(e) => invalid.push((tx, e)),
+        }
+    }
+    (valid, invalid)
+}
+
+/// Wraps a low-level CSV error with the line number it occurred on
+fn csv_row_error(source: csv::Error) -> TransactionError {
+    TransactionError::Csv {
+        line: source.position().map_or(0, |position| position.line()),
+        source,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transaction::TransactionType;
+
+    /// Tests loading a JSON transaction file from disk
+    #[test]
+    fn test_load_transactions_from_json() {
+        let path = std::env::temp_dir().join("ryz_load_transactions_from_json.json");
+        fs::write(
+            &path,
+            r#"[
+                {"transaction_type": "deposit", "wallet_address": "ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3", "amount": 100},
+                {"transaction_type": "withdrawal", "wallet_address": "ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3", "amount": 30}
+            ]"#,
+        )
+        .unwrap();
+
+        let transactions = load_transactions_from_json(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(transactions.len(), 2);
+        assert_eq!(transactions[0].transaction_type(), &TransactionType::Deposit);
+        assert_eq!(transactions[1].amount(), 30);
+    }
+
+    /// Tests that invalid transactions in a JSON file are rejected
+    #[test]
+    fn test_load_transactions_from_json_invalid_amount() {
+        let path = std::env::temp_dir().join("ryz_load_transactions_invalid_amount.json");
+        fs::write(
+            &path,
+            r#"[{"transaction_type": "deposit", "wallet_address": "ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3", "amount": 0}]"#,
+        )
+        .unwrap();
+
+        let result = load_transactions_from_json(&path);
+        fs::remove_file(&path).unwrap();
+        assert!(matches!(result, Err(TransactionError::InvalidTransaction { index: 0, .. })));
+    }
+
+    /// Tests loading newline-delimited JSON with a blank line
+    #[test]
+    fn test_load_transactions_from_ndjson() {
+        let data = r#"{"transaction_type": "deposit", "wallet_address": "ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3", "amount": 100}
+
+{"transaction_type": "withdrawal", "wallet_address": "ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3", "amount": 40}
+"#;
+        let transactions = load_transactions_from_ndjson(data.as_bytes()).unwrap();
+        assert_eq!(transactions.len(), 2);
+        assert_eq!(transactions[1].amount(), 40);
+    }
+
+    /// Tests that a CSV row with an unknown type is reported with its line number
+    #[test]
+    fn test_load_transactions_from_csv_bad_type() {
+        let data = "type,wallet,amount\n\
+                    deposit,ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3,100\n\
+                    refund,ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3,20\n";
+
+        let result = load_transactions_from_csv(data.as_bytes());
+        assert!(matches!(
+            result,
+            Err(TransactionError::InvalidCsvRow { line: 3, reason }) if reason.contains("refund")
+        ));
+    }
+
+    /// Tests that a CSV row with a non-numeric amount reports its line and keeps the parse error as its source
+    #[test]
+    fn test_load_transactions_from_csv_bad_amount() {
+        use std::error::Error;
+
+        let data = "type,wallet,amount\n\
+                    deposit,ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3,100\n\
+                    deposit,ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3,1e3\n";
+
+        let error = load_transactions_from_csv(data.as_bytes()).unwrap_err();
+        assert!(matches!(error, TransactionError::ParseRow { line: 3, .. }));
+        assert!(error.source().unwrap().downcast_ref::<std::num::ParseIntError>().is_some());
+    }
+
+    /// Tests that a CSV row with too many fields reports its line and keeps the CSV error as its source
+    #[test]
+    fn test_load_transactions_from_csv_ragged_row() {
+        use std::error::Error;
+
+        let data = "type,wallet,amount\n\
+                    deposit,ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3,100\n\
+                    deposit,ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3,100,extra\n";
+
+        let error = load_transactions_from_csv(data.as_bytes()).unwrap_err();
+        assert!(matches!(error, TransactionError::Csv { line: 3, .. }));
+        assert!(error.source().unwrap().downcast_ref::<csv::Error>().is_some());
+    }
+
+    /// Tests that a quoted CSV amount with a thousands separator is accepted
+    #[test]
+    fn test_load_transactions_from_csv_thousands_separator() {
+        let data = "type,wallet,amount\n\
+                    deposit,ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3,\"1,000\"\n";
+
+        let transactions = load_transactions_from_csv(data.as_bytes()).unwrap();
+        assert_eq!(transactions[0].amount(), 1_000);
+    }
+
+    /// Tests loading well-formed CSV rows
+    #[test]
+    fn test_load_transactions_from_csv() {
+        let data = "type,wallet,amount\n\
+                    Deposit,ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3,100\n\
+                    withdrawal,ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3,25\n";
+
+        let transactions = load_transactions_from_csv(data.as_bytes()).unwrap();
+        assert_eq!(transactions.len(), 2);
+        assert_eq!(transactions[0].transaction_type(), &TransactionType::Deposit);
+        assert_eq!(transactions[1].transaction_type(), &TransactionType::Withdrawal);
+        assert_eq!(transactions[1].amount(), 25);
+    }
+
+    /// Tests that every invalid transaction is reported with its index
+    #[test]
+    fn test_validate_all_reports_every_failure() {
+        let valid = Transaction::new(TransactionType::Deposit, "ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3", 100).unwrap();
+        let mut zero = valid.clone();
+        zero.amount = 0;
+        let mut bad_address = valid.clone();
+        bad_address.wallet_address = "not-a-wallet".to_string();
+
+        let failures = validate_all(&[valid.clone(), zero, valid.clone(), bad_address]);
+        assert_eq!(failures.len(), 2);
+        assert!(matches!(failures[0], (1, TransactionError::ZeroAmount)));
+        assert!(matches!(&failures[1], (3, TransactionError::InvalidWalletAddress(address)) if address == "not-a-wallet"));
+        assert!(validate_all(&[valid]).is_empty());
+    }
+
+    /// Tests that valid and invalid transactions are separated, keeping input order
+    #[test]
+    fn test_partition_valid() {
+        let valid = Transaction::new(TransactionType::Deposit, "ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3", 100).unwrap();
+        let mut zero = valid.clone();
+        zero.amount = 0;
+        let mut bad_address = valid.clone();
+        bad_address.wallet_address = "not-a-wallet".to_string();
+        let mut second = valid.clone();
+        second.amount = 200;
+
+        let (ok, failed) = partition_valid(vec![valid.clone(), zero.clone(), second.clone(), bad_address.clone()]);
+        assert_eq!(ok, vec![valid, second]);
+        assert_eq!(failed.len(), 2);
+        assert_eq!(failed[0].0, zero);
+        assert!(matches!(failed[0].1, TransactionError::ZeroAmount));
+        assert_eq!(failed[1].0, bad_address);
+        assert!(matches!(failed[1].1, TransactionError::InvalidWalletAddress(_)));
+    }
+
+    /// Tests converting one buy and one sell exchange record
+    #[test]
+    fn test_exchange_records_to_transactions() {
+        let records: Vec<ExchangeRecord> = serde_json::from_str(
+            r#"[
+                {"wallet": "ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3", "side": "buy", "base_amount": 2000000000, "quote_amount": 300000000},
+                {"wallet": "ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3", "side": "SELL", "base_amount": 500000000, "quote_amount": 80000000}
+            ]"#,
+        )
+        .unwrap();
+
+        let transactions: Vec<Transaction> = records.into_iter().map(|r| Transaction::try_from(r).unwrap()).collect();
+        assert_eq!(transactions[0].transaction_type(), &TransactionType::Deposit);
+        assert_eq!(transactions[0].amount(), 2_000_000_000);
+        assert_eq!(transactions[1].transaction_type(), &TransactionType::Withdrawal);
+        assert_eq!(transactions[1].amount(), 500_000_000);
+    }
+
+    /// Tests that an exchange record with an unknown side is rejected
+    #[test]
+    fn test_exchange_record_invalid_side() {
+        let record = ExchangeRecord {
+            wallet: "ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3".to_string(),
+            side: "short".to_string(),
+            base_amount: 1,
+            quote_amount: 1,
+        };
+        assert!(matches!(
+            Transaction::try_from(record),
+            Err(TransactionError::UnknownTransactionType(side)) if side == "short"
+        ));
+    }
+}
@@ -0,0 +1,70 @@
+use crate::error::TransactionError;
+
+/// The number of basis points in 100%
+const BPS_PER_UNIT: i128 = 10_000;
+
+/// The number of days interest accrues over in a year
+const DAYS_PER_YEAR: i128 = 365;
+
+/// Calculates the simple interest accrued on a principal
+///
+/// Computes `principal * annual_bps / 10_000 * days / 365` entirely in integer math, so
+/// there is no floating-point drift. The division happens last, truncating toward zero,
+/// so fractions of a lamport are never paid out.
+///
+/// # Arguments
+///
+/// * `principal` - The balance interest accrues on, in lamports
+/// * `annual_bps` - The annual interest rate, in basis points (500 = 5%)
+/// * `days` - How many days interest accrues for
+///
+/// # Returns
+///
+/// * `Ok(i64)` - The accrued interest, in lamports
+/// * `Err(TransactionError)` - If the interest does not fit in an i64
+///
+pub fn accrue_simple_interest(principal: i64, annual_bps: u32, days: u32) -> Result<i64, TransactionError> {
+    // Widen before multiplying so only genuinely unrepresentable interest is rejected
+    let numerator = i128::from(principal)
+        .checked_mul(i128::from(annual_bps))
+        .and_then(|n| n.checked_mul(i128::from(days)))
+        .ok_or(TransactionError::InterestOverflow(principal))?;
+
+    i64::try_from(numerator / (BPS_PER_UNIT * DAYS_PER_YEAR)).map_err(|_| TransactionError::InterestOverflow(principal))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::units::LAMPORTS_PER_SOL;
+
+    /// Tests a one-year 500 bps accrual on 100 SOL
+    #[test]
+    fn test_one_year_accrual() {
+        assert_eq!(
+            accrue_simple_interest(100 * LAMPORTS_PER_SOL, 500, 365).unwrap(),
+            5 * LAMPORTS_PER_SOL
+        );
+    }
+
+    /// Tests that partial lamports are truncated rather than rounded
+    #[test]
+    fn test_accrual_truncates() {
+        // 1_000 lamports at 5% for 1 day is 0.13... lamports
+        assert_eq!(accrue_simple_interest(1_000, 500, 1).unwrap(), 0);
+        assert_eq!(accrue_simple_interest(1_000_000, 500, 1).unwrap(), 136);
+    }
+
+    /// Tests that interest too large for an i64 is rejected
+    #[test]
+    fn test_accrual_overflow() {
+        assert!(matches!(
+            accrue_simple_interest(i64::MAX, u32::MAX, u32::MAX),
+            Err(TransactionError::InterestOverflow(i64::MAX))
+        ));
+        assert!(matches!(
+            accrue_simple_interest(i64::MAX, 10_000, 730),
+            Err(TransactionError::InterestOverflow(_))
+        ));
+    }
+}
@@ -0,0 +1,86 @@
+//! A simple wallet transaction processing system that handles deposits and withdrawals.
+//! This crate provides functionality to calculate wallet balances based on transaction history.
+
+mod address;
+mod analytics;
+mod balance;
+mod builder;
+mod cache;
+mod cluster;
+mod config;
+#[cfg(feature = "crypto")]
+mod crypto;
+#[cfg(feature = "decimal")]
+mod decimal;
+mod dedup;
+mod error;
+mod export;
+mod fees;
+mod history;
+mod import;
+mod interest;
+#[cfg(feature = "parallel")]
+mod parallel;
+mod snapshot;
+mod summary;
+mod transaction;
+mod units;
+mod unsigned;
+mod wallet;
+#[cfg(feature = "wasm")]
+mod wasm;
+
+pub use address::{
+    is_valid_solana_address, normalize_wallet_address, AddressValidator, EthereumValidator, SolanaValidator,
+    WalletAddress,
+};
+pub use analytics::{
+    active_wallets_since, amount_histogram, categorize, count_by_type, largest_transaction, negative_balance_wallets,
+    net_flow, net_settlements, threshold_alerts, top_wallets_by_balance, NetFlow, ThresholdAlert,
+};
+pub use balance::{
+    aggregate_balance, balances_sorted, calculate_all_balances, calculate_available_balance,
+    calculate_balance_from_iter, calculate_balance_min_confirmations, calculate_confirmed_balance,
+    calculate_native_balances, calculate_wallet_balance, calculate_wallet_balance_capped,
+    calculate_wallet_balance_checked, calculate_wallet_balance_dedup, calculate_wallet_balance_gross,
+    calculate_wallet_balance_strict, calculate_wallet_balance_with_config, calculate_wallet_balance_with_floor,
+    calculate_wallet_balance_with_freeze, calculate_wallet_balance_with_limit, calculate_wallet_balance_with_schedule,
+    calculate_wallet_balance_with_validator, calculate_wallet_balance_without_dust, percentage_withdrawal,
+    project_with_fee_multiplier, simulate, transactions_for_wallet,
+};
+pub use builder::TransactionBuilder;
+pub use cache::BalanceCache;
+pub use cluster::{BalanceRequest, Cluster};
+pub use config::ValidationConfig;
+#[cfg(feature = "crypto")]
+pub use crypto::verify_signature;
+#[cfg(feature = "decimal")]
+pub use decimal::{calculate_wallet_balance_decimal, DecimalAmount, DecimalTransaction};
+pub use dedup::{dedup_transactions, diff_transactions, find_suspected_duplicates, merge_sources};
+pub use error::TransactionError;
+pub use export::{export_balances_json, render_statement};
+pub use fees::FeeSchedule;
+pub use history::{
+    balance_at, balance_history, daily_balances, filter_by_memo_substring, filter_by_range, filter_dust,
+    first_overdraft, rollback_last, sort_transactions, RunningBalance,
+};
+pub use import::{
+    load_transactions_from_csv, load_transactions_from_json, load_transactions_from_ndjson, parse_transactions_json,
+    partition_valid, validate_all, ExchangeRecord,
+};
+pub use interest::accrue_simple_interest;
+#[cfg(feature = "parallel")]
+pub use parallel::calculate_all_balances_parallel;
+pub use snapshot::{apply_since, balance_delta, snapshot, Snapshot};
+pub use summary::{
+    balance_by_type, balances_match_within, calculate_wallet_summary, reconcile, ReconResult, WalletSummary,
+};
+pub use transaction::{parse_amount, Transaction, TransactionStatus, TransactionType};
+pub use units::{
+    balance_in_usd, format_sol, format_token_amount, lamports_to_sol, report_balance, sol_to_lamports,
+    MultiCurrencyBalance, TokenInfo, LAMPORTS_PER_SOL,
+};
+pub use unsigned::{calculate_wallet_balance_u64, UnsignedTransaction};
+pub use wallet::Wallet;
+#[cfg(feature = "wasm")]
+pub use wasm::wasm_calculate_balance;
@@ -1,214 +1,127 @@
-//! A simple wallet transaction processing system that handles deposits and withdrawals.
-//! This module provides functionality to calculate wallet balances based on transaction history.
-
-use thiserror::Error;
-use regex::Regex;
-
-/// Represents possible errors that can occur during transaction processing
-#[derive(Error, Debug)]
-pub enum TransactionError {
-    /// Returned when the wallet address is invalid or empty
-    #[error("Invalid wallet address: {0}")]
-    InvalidWalletAddress(String),
-    /// Returned when a transaction amount is zero
-    #[error("Amount cannot be zero")]
-    ZeroAmount,
-    #[error("No transactions found for wallet {0}")]
-    NoTransactions(String),
+//! Binary that calculates wallet balances.
+//!
+//! Transactions are loaded from `--input <path>` in the `--format` it names (JSON by default).
+//! Without an input file, transactions piped in as newline-delimited JSON are used, and
+//! otherwise a hardcoded demo history. The balance of `--wallet <address>` is printed, or
+//! that of every wallet when no wallet is given.
+//!
+//! The cluster the transactions belong to is taken from `--cluster <name>`, falling back to
+//! the `SOLANA_CLUSTER` environment variable and then to mainnet.
+
+use std::fs::File;
+use std::io::{self, BufReader, IsTerminal};
+use std::path::{Path, PathBuf};
+
+use clap::{Parser, ValueEnum};
+use ryz_coding_challenge::{
+    calculate_all_balances, calculate_wallet_balance, lamports_to_sol, load_transactions_from_csv,
+    load_transactions_from_json, load_transactions_from_ndjson, BalanceRequest, Cluster, Transaction, TransactionError,
+    TransactionType, WalletAddress,
+};
+
+const ALICE: &str = "ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3";
+const BOB: &str = "9jLkNAaW9E47LQMHvjohy2uAAyr1331bAxgJKFRU7wF6";
+
+/// Command-line arguments
+#[derive(Debug, Parser)]
+#[command(version, about = "Calculates wallet balances from a transaction history")]
+struct Args {
+    /// File to load transactions from
+    #[arg(long, value_name = "PATH")]
+    input: Option<PathBuf>,
+    /// Format of the input file
+    #[arg(long, value_enum, default_value_t = InputFormat::Json)]
+    format: InputFormat,
+    /// Wallet to print the balance of; every wallet's balance is printed if omitted
+    #[arg(long, value_name = "ADDRESS")]
+    wallet: Option<String>,
+    /// Cluster the transactions belong to: mainnet, devnet, or testnet
+    #[arg(long, env = "SOLANA_CLUSTER", value_name = "NAME")]
+    cluster: Option<String>,
 }
 
-/// Represents the type of transaction
-#[derive(Debug, Clone, PartialEq)]
-enum TransactionType {
-    /// Adds funds to the wallet
-    Deposit,
-    /// Removes funds from the wallet
-    Withdrawal,
+/// Supported input file formats
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum InputFormat {
+    /// A JSON array of transactions
+    Json,
+    /// CSV with a header row
+    Csv,
 }
 
-/// Represents a single transaction with its associated data
-#[derive(Debug, Clone)]
-struct Transaction {
-    /// The type of transaction (Deposit or Withdrawal)
-    transaction_type: TransactionType,
-    /// The wallet address associated with the transaction
-    wallet_address: String,
-    /// The amount of the transaction (must be non-zero)
-    amount: i64,
+/// Builds the example transaction history used by the demo
+fn demo_transactions() -> Result<Vec<Transaction>, TransactionError> {
+    Ok(vec![
+        Transaction::new(TransactionType::Deposit, ALICE, 100)?,
+        Transaction::new(TransactionType::Withdrawal, ALICE, 50)?,
+        Transaction::new(TransactionType::Deposit, BOB, 200)?,
+        Transaction::new(TransactionType::Withdrawal, BOB, 75)?,
+        Transaction::new(TransactionType::Deposit, ALICE, 25)?,
+    ])
 }
 
-/// Validates a Solana wallet address format
-/// it uses the Solana address format
-///
-/// # Arguments
-///
-/// * `address` - The address to validate
-///
-/// # Returns
-///
-/// * `bool` - True if the address is valid, false otherwise
-/// 
-fn is_valid_solana_address(address: &str) -> bool {
-    // Basic Solana address validation (alphanumeric, 32-44 chars)
-    let re = Regex::new(r"^[1-9A-HJ-NP-Za-km-z]{32,44}$").unwrap();
-    re.is_match(address)
-}
-
-/// Calculates the current balance for a given wallet address based on its transaction history
-/// it uses the Solana address format
-///
-/// # Arguments
-///
-/// * `wallet_address` - The address of the wallet to calculate the balance for
-/// * `transactions` - A slice of transactions to process
-///
-/// # Returns
-///
-/// * `Ok(i64)` - The calculated balance if successful
-/// * `Err(TransactionError)` - If there's an error processing the transactions
-///
-fn calculate_wallet_balance(wallet_address: &str, transactions: &[Transaction]) -> Result<i64, TransactionError> {
-    // Validate wallet address
-    if wallet_address.is_empty() {
-        return Err(TransactionError::InvalidWalletAddress("Empty address".to_string()));
+/// Loads transactions from `path` in the given format
+fn load_file(path: &Path, format: InputFormat) -> Result<Vec<Transaction>, TransactionError> {
+    match format {
+        InputFormat::Json => load_transactions_from_json(path),
+        InputFormat::Csv => load_transactions_from_csv(BufReader::new(File::open(path)?)),
     }
-
-    // Validate Solana address format
-    if !is_valid_solana_address(wallet_address) {
-        return Err(TransactionError::InvalidWalletAddress(wallet_address.to_string()));
-    }
-
-    // Check if there are any transactions list is empty
-    if transactions.is_empty() {
-        return Err(TransactionError::NoTransactions(wallet_address.to_string()));
-    }
-
-    // Process transactions and calculate balance
-    transactions
-        .iter()
-        .filter(|tx| tx.wallet_address == wallet_address)
-        .try_fold(0i64, |acc, tx| {
-            // Validate transaction amount
-            if tx.amount == 0 {
-                return Err(TransactionError::ZeroAmount);
-            }
-
-            // Update balance based on transaction type
-            match tx.transaction_type {
-                TransactionType::Deposit => Ok(acc + tx.amount),
-                TransactionType::Withdrawal => Ok(acc - tx.amount),
-            }
-        })
 }
 
-fn main() {
-    // Example transactions
-    let transactions = vec![
-        Transaction {
-            transaction_type: TransactionType::Deposit,
-            wallet_address: "ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3".to_string(),
-            amount: 100,
-        },
-        Transaction {
-            transaction_type: TransactionType::Withdrawal,
-            wallet_address: "ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3".to_string(),
-            amount: 50,
-        },
-        Transaction {
-            transaction_type: TransactionType::Deposit,
-            wallet_address: "BOBqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3".to_string(), 
-            amount: 200,
-        },
-        Transaction {
-            transaction_type: TransactionType::Withdrawal,
-            wallet_address: "BOBqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3".to_string(),
-            amount: 75,
-        },
-        Transaction {
-            transaction_type: TransactionType::Deposit,
-            wallet_address: "ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3".to_string(),
-            amount: 25,
-        },
-    ];
-
-    // Calculate and display balance
-    match calculate_wallet_balance("ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3", &transactions) {
-        Ok(balance) => println!("Balance for ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3: {}", balance),
-        Err(e) => eprintln!("Error calculating balance: {}", e),
-    }
+/// Calculates and displays a single wallet's balance
+fn print_wallet_balance(request: &BalanceRequest, transactions: &[Transaction]) -> Result<(), TransactionError> {
+    let balance = calculate_wallet_balance(&WalletAddress::parse(&request.wallet)?, transactions)?;
+    println!(
+        "Balance for {} on {}: {} lamports ({} SOL)",
+        request.wallet,
+        request.cluster,
+        balance,
+        lamports_to_sol(balance)
+    );
+    Ok(())
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// Calculates and displays every wallet's balance, sorted by wallet
+fn print_all_balances(cluster: Cluster, transactions: &[Transaction]) -> Result<(), TransactionError> {
+    let mut balances: Vec<_> = calculate_all_balances(transactions)?.into_iter().collect();
+    balances.sort();
 
-    /// Tests basic balance calculation with deposit and withdrawal
-    #[test]
-    fn test_calculate_wallet_balance() {
-        let transactions = vec![
-            Transaction {
-                transaction_type: TransactionType::Deposit,
-                wallet_address: "ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3".to_string(),
-                amount: 100,
-            },
-            Transaction {
-                transaction_type: TransactionType::Withdrawal,
-                wallet_address: "ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3".to_string(),
-                amount: 50,
-            },
-            Transaction {
-                transaction_type: TransactionType::Deposit,
-                wallet_address: "BOBqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3".to_string(),
-                amount: 200,
-            },
-            Transaction {
-                transaction_type: TransactionType::Withdrawal,
-                wallet_address: "BOBqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3".to_string(),
-                amount: 75,
-            },
-            Transaction {
-                transaction_type: TransactionType::Deposit,
-                wallet_address: "ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3".to_string(),
-                amount: 25,
-            },
-        ];
+    println!("Cluster: {}", cluster);
 
-        let result = calculate_wallet_balance("ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3", &transactions);
-        assert!(result.is_ok());
-        assert_eq!(result.unwrap(), 75);
-    }
-
-    /// Tests error handling for invalid wallet addresses
-    #[test]
-    fn test_invalid_wallet_address() {
-        let transactions = vec![];
-        assert!(matches!(
-            calculate_wallet_balance("", &transactions),
-            Err(TransactionError::InvalidWalletAddress(_))
-        ));
+    for ((wallet, mint), balance) in balances {
+        match mint {
+            None => println!("{}: {} lamports ({} SOL)", wallet, balance, lamports_to_sol(balance)),
+            Some(mint) => println!("{} [{}]: {}", wallet, mint, balance),
+        }
     }
+    Ok(())
+}
 
-    /// Tests error handling for empty transaction list
-    #[test]
-    fn test_empty_transaction_list() {
-        let transactions = vec![];
-        assert!(matches!(
-            calculate_wallet_balance("ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3", &transactions),
-            Err(TransactionError::NoTransactions(_))
-        ));
+/// Loads the selected transactions and prints the requested balances
+fn run(args: Args) -> Result<(), TransactionError> {
+    let cluster = match &args.cluster {
+        Some(name) => name.parse()?,
+        None => Cluster::default(),
+    };
+
+    let (transactions, wallet) = if let Some(path) = &args.input {
+        (load_file(path, args.format)?, args.wallet)
+    } else if !io::stdin().is_terminal() {
+        (load_transactions_from_ndjson(io::stdin().lock())?, args.wallet)
+    } else {
+        // The demo shows Alice's balance unless another wallet is asked for
+        (demo_transactions()?, args.wallet.or_else(|| Some(ALICE.to_string())))
+    };
+
+    match wallet {
+        Some(wallet) => print_wallet_balance(&BalanceRequest { cluster, wallet }, &transactions),
+        None => print_all_balances(cluster, &transactions),
     }
+}
 
-    /// Tests error handling for zero amount transactions
-    #[test]
-    fn test_zero_amount() {
-        let transactions = vec![Transaction {
-            transaction_type: TransactionType::Deposit,
-            wallet_address: "ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3".to_string(),
-            amount: 0,
-        }];
-        assert!(matches!(
-            calculate_wallet_balance("ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3", &transactions),
-            Err(TransactionError::ZeroAmount)
-        ));
+fn main() {
+    if let Err(e) = run(Args::parse()) {
+        eprintln!("Error calculating balance: {}", e);
+        std::process::exit(e.exit_code());
     }
 }
@@ -0,0 +1,161 @@
+use std::collections::HashMap;
+
+use rayon::prelude::*;
+
+use crate::balance::{locate_error, transaction_delta, OpeningBalanceGuard};
+use crate::error::TransactionError;
+use crate::transaction::{Transaction, TransactionType};
+
+/// Calculates the balance of every wallet in the transaction history using all available cores
+///
+/// Produces the same result as [`calculate_all_balances`](crate::calculate_all_balances).
+/// Validation runs first, and the error for the earliest invalid transaction in the slice is
+/// returned, so failures are deterministic regardless of thread scheduling. A sequential pass
+/// then rejects any opening balance that is not its wallet's first transaction. Each thread then
+/// reduces its share of the slice into per-wallet sums, which are merged and checked for
+/// overflow once at the end.
+///
+/// # Arguments
+///
+/// * `transactions` - A slice of transactions to process
+///
+/// # Returns
+///
+/// * `Ok(HashMap<(String, Option<String>), i64>)` - The balance of each wallet, keyed by `(address, mint)`
+/// * `Err(TransactionError)` - If any transaction is invalid or a balance overflows
+///
+pub fn calculate_all_balances_parallel(
+    transactions: &[Transaction],
+) -> Result<HashMap<(String, Option<String>), i64>, TransactionError> {
+    // Surface the first invalid transaction in slice order
+    if let Some(e) = transactions
+        .par_iter()
+        .enumerate()
+        .find_map_first(|(index, tx)| tx.validate().err().map(|e| locate_error(index, e)))
+    {
+        return Err(e);
+    }
+
+    // Summing deltas cannot tell where an opening balance falls, so check its position up front
+    let mut opening = OpeningBalanceGuard::default();
+    for (index, tx) in transactions.iter().enumerate() {
+        opening.record(tx, &tx.wallet_address).map_err(|e| locate_error(index, e))?;
+        if let TransactionType::Transfer { to } = &tx.transaction_type {
+            opening.record(tx, to).map_err(|e| locate_error(index, e))?;
+        }
+    }
+
+    // Reduce each wallet's net change in parallel, using i128 so partial sums cannot overflow
+    let sums = transactions
+        .par_iter()
+        .try_fold(HashMap::new, |mut sums: HashMap<(String, Option<String>), i128>, tx| {
+            let mut wallets = vec![tx.wallet_address.as_str()];
+            if let TransactionType::Transfer { to } = &tx.transaction_type {
+                wallets.push(to);
+            }
+            for wallet in wallets {
+                let delta = transaction_delta(tx, wallet)?;
+                *sums.entry((wallet.to_string(), tx.mint.clone())).or_insert(0) += delta;
+            }
+            Ok::<_, TransactionError>(sums)
+        })
+        .try_reduce(HashMap::new, |mut merged, sums| {
+            for (key, sum) in sums {
+                *merged.entry(key).or_insert(0) += sum;
+            }
+            Ok(merged)
+        })?;
+
+    // Narrow back to i64, reporting the lowest overflowing address for determinism
+    let mut overflowed: Vec<&String> = sums
+        .iter()
+        .filter(|(_, sum)| i64::try_from(**sum).is_err())
+        .map(|((wallet, _), _)| wallet)
+        .collect();
+    overflowed.sort();
+    if let Some(wallet) = overflowed.first() {
+        return Err(TransactionError::BalanceOverflow(wallet.to_string()));
+    }
+    Ok(sums
+        .into_iter()
+        .map(|(key, sum)| (key, sum as i64))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::balance::calculate_all_balances;
+
+    const WALLETS: [&str; 4] = [
+        "ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3",
+        "9jLkNAaW9E47LQMHvjohy2uAAyr1331bAxgJKFRU7wF6",
+        "68GLr8rYqhXTRgYuH5MN7BeswuPxjeEZRLMzunr9JQCt",
+        "7bDXTe5fFehXPtVMMh9cL5hxcjNenk8g34eCNRTiuBTs",
+    ];
+
+    /// Tests that the parallel result matches the serial result on a large shuffled input
+    #[test]
+    fn test_parallel_matches_serial() {
+        let mut transactions: Vec<Transaction> = (0..100_000)
+            .map(|i| {
+                let wallet = WALLETS[i % WALLETS.len()];
+                let transaction_type = match i % 3 {
+                    0 => TransactionType::Withdrawal,
+                    1 => TransactionType::Transfer { to: WALLETS[(i + 1) % WALLETS.len()].to_string() },
+                    _ => TransactionType::Deposit,
+                };
+                Transaction::new(transaction_type, wallet, (i % 997 + 1) as i64).unwrap()
+            })
+            .collect();
+
+        // Deterministic Fisher-Yates shuffle driven by a simple LCG
+        let mut state: u64 = 0x2545_F491_4F6C_DD1D;
+        for i in (1..transactions.len()).rev() {
+            state = state.wrapping_mul(6_364_136_223_846_793_005).wrapping_add(1);
+            transactions.swap(i, (state >> 33) as usize % (i + 1));
+        }
+
+        assert_eq!(
+            calculate_all_balances_parallel(&transactions).unwrap(),
+            calculate_all_balances(&transactions).unwrap()
+        );
+    }
+
+    /// Tests that the earliest invalid transaction is reported
+    #[test]
+    fn test_parallel_reports_first_error() {
+        let mut transactions: Vec<Transaction> = (0..10_000)
+            .map(|_| Transaction::new(TransactionType::Deposit, WALLETS[0], 1).unwrap())
+            .collect();
+        transactions[4_000].amount = -4;
+        transactions[9_000].amount = -9;
+
+        assert!(matches!(
+            calculate_all_balances_parallel(&transactions),
+            Err(TransactionError::InvalidTransaction { index: 4_000, .. })
+        ));
+    }
+
+    /// Tests that an opening balance after earlier activity is rejected, as it is serially
+    #[test]
+    fn test_parallel_misplaced_opening_balance() {
+        let transactions = vec![
+            Transaction::new(TransactionType::OpeningBalance, WALLETS[0], 500).unwrap(),
+            Transaction::new(TransactionType::Transfer { to: WALLETS[1].to_string() }, WALLETS[0], 100).unwrap(),
+            Transaction::new(TransactionType::OpeningBalance, WALLETS[1], 300).unwrap(),
+        ];
+
+        for result in [calculate_all_balances(&transactions), calculate_all_balances_parallel(&transactions)] {
+            assert!(matches!(
+                result,
+                Err(TransactionError::InvalidTransaction { index: 2, source })
+                    if matches!(&*source, TransactionError::MisplacedOpeningBalance(wallet) if wallet == WALLETS[1])
+            ));
+        }
+
+        // The same opening balance is accepted as the wallet's first transaction
+        let valid = [&transactions[2..], &transactions[..2]].concat();
+        assert_eq!(calculate_all_balances_parallel(&valid).unwrap(), calculate_all_balances(&valid).unwrap());
+    }
+}
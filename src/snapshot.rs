@@ -0,0 +1,174 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::address::{validate_address, validate_mint};
+use crate::balance::{apply_transaction, locate_error, OpeningBalanceGuard};
+use crate::error::TransactionError;
+use crate::transaction::{Transaction, TransactionType};
+
+/// The native SOL balance of every wallet at a point in time, suitable for persisting
+///
+/// A long-running service can store a snapshot and later bring it up to date with
+/// [`apply_since`] instead of recalculating from the full history.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// The native balance of each wallet, keyed by address
+    pub balances: HashMap<String, i64>,
+    /// The time the snapshot was taken at, in Unix seconds (inclusive)
+    pub as_of: i64,
+}
+
+/// Takes a snapshot of every wallet's native SOL balance as of a point in time
+///
+/// # Arguments
+///
+/// * `transactions` - A slice of transactions to process
+/// * `as_of` - The cutoff time, in Unix seconds; only transactions at or before it count
+///
+/// # Returns
+///
+/// * `Ok(Snapshot)` - The balances as of `as_of`
+/// * `Err(TransactionError)` - If any counted transaction is invalid or a balance overflows
+///
+pub fn snapshot(transactions: &[Transaction], as_of: i64) -> Result<Snapshot, TransactionError> {
+    let mut balances = HashMap::new();
+    let mut opening = OpeningBalanceGuard::default();
+    for (index, tx) in transactions.iter().enumerate().filter(|(_, tx)| tx.timestamp <= as_of) {
+        apply_native(&mut balances, &mut opening, tx).map_err(|e| locate_error(index, e))?;
+    }
+    Ok(Snapshot { balances, as_of })
+}
+
+/// Brings a snapshot up to date with the transactions made after it was taken
+///
+/// Transactions at or before the snapshot's `as_of` are already reflected in it and are
+/// ignored, so the full history can be passed without double-counting.
+///
+/// # Arguments
+///
+/// * `snapshot` - The snapshot to start from
+/// * `transactions` - A slice of transactions to process
+///
+/// # Returns
+///
+/// * `Ok(HashMap<String, i64>)` - The current native balance of each wallet, keyed by address
+/// * `Err(TransactionError)` - If any new transaction is invalid or a balance overflows
+///
+pub fn apply_since(snapshot: &Snapshot, transactions: &[Transaction]) -> Result<HashMap<String, i64>, TransactionError> {
+    let mut balances = snapshot.balances.clone();
+    let mut opening = OpeningBalanceGuard::with_native_history(snapshot.balances.keys());
+    for (index, tx) in transactions.iter().enumerate().filter(|(_, tx)| tx.timestamp > snapshot.as_of) {
+        apply_native(&mut balances, &mut opening, tx).map_err(|e| locate_error(index, e))?;
+    }
+    Ok(balances)
+}
+
+/// Compares two sets of balances, returning how much each changed wallet moved
+///
+/// A wallet missing from either map is treated as having a balance of zero there, so a
+/// wallet new in `current` is reported with its whole balance. Deltas saturate at the
+/// bounds of `i64`.
+///
+/// # Arguments
+///
+/// * `previous` - The balances from the last poll, keyed by address
+/// * `current` - The latest balances, keyed by address
+///
+/// # Returns
+///
+/// * `HashMap<String, i64>` - The signed change of every wallet whose balance differs
+///
+pub fn balance_delta(previous: &HashMap<String, i64>, current: &HashMap<String, i64>) -> HashMap<String, i64> {
+    previous
+        .keys()
+        .chain(current.keys())
+        .filter_map(|wallet| {
+            let before = previous.get(wallet).copied().unwrap_or(0);
+            let after = current.get(wallet).copied().unwrap_or(0);
+            let delta = after.saturating_sub(before);
+            (delta != 0).then(|| (wallet.clone(), delta))
+        })
+        .collect()
+}
+
+/// Applies a transaction to the native balances of its source and destination
+///
+/// SPL token transactions are validated but leave the balances unchanged.
+fn apply_native(
+    balances: &mut HashMap<String, i64>,
+    opening: &mut OpeningBalanceGuard,
+    tx: &Transaction,
+) -> Result<(), TransactionError> {
+    validate_address(&tx.wallet_address)?;
+    if let TransactionType::Transfer { to } = &tx.transaction_type {
+        validate_address(to)?;
+    }
+    if let Some(mint) = &tx.mint {
+        return validate_mint(mint);
+    }
+
+    opening.record(tx, &tx.wallet_address)?;
+    let balance = balances.entry(tx.wallet_address.clone()).or_insert(0);
+    *balance = apply_transaction(*balance, tx, &tx.wallet_address)?;
+    if let TransactionType::Transfer { to } = &tx.transaction_type {
+        opening.record(tx, to)?;
+        let balance = balances.entry(to.clone()).or_insert(0);
+        *balance = apply_transaction(*balance, tx, to)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::balance::calculate_native_balances;
+
+    const ALICE: &str = "ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3";
+    const BOB: &str = "9jLkNAaW9E47LQMHvjohy2uAAyr1331bAxgJKFRU7wF6";
+    const CAROL: &str = "68GLr8rYqhXTRgYuH5MN7BeswuPxjeEZRLMzunr9JQCt";
+    const DAVE: &str = "7bDXTe5fFehXPtVMMh9cL5hxcjNenk8g34eCNRTiuBTs";
+
+    /// Tests that a snapshot plus the later transactions equals a full recalculation
+    #[test]
+    fn test_snapshot_plus_delta_equals_full() {
+        let transactions = vec![
+            Transaction::new(TransactionType::Deposit, ALICE, 1_000).unwrap().with_timestamp(100),
+            Transaction::new(TransactionType::Transfer { to: BOB.to_string() }, ALICE, 300)
+                .unwrap()
+                .with_timestamp(200),
+            Transaction::new(TransactionType::Withdrawal, BOB, 50).unwrap().with_timestamp(300),
+            Transaction::new(TransactionType::Transfer { to: CAROL.to_string() }, ALICE, 100)
+                .unwrap()
+                .with_timestamp(400),
+            Transaction::new(TransactionType::Deposit, BOB, 25).unwrap().with_timestamp(500),
+        ];
+
+        let saved = snapshot(&transactions, 250).unwrap();
+        assert_eq!(saved.balances, HashMap::from([(ALICE.to_string(), 700), (BOB.to_string(), 300)]));
+
+        let restored: Snapshot = serde_json::from_str(&serde_json::to_string(&saved).unwrap()).unwrap();
+        assert_eq!(
+            apply_since(&restored, &transactions).unwrap(),
+            calculate_native_balances(&transactions).unwrap()
+        );
+    }
+
+    /// Tests that only changed wallets are reported, with new wallets measured from zero
+    #[test]
+    fn test_balance_delta() {
+        let previous = HashMap::from([(ALICE.to_string(), 500), (BOB.to_string(), 300), (CAROL.to_string(), 10)]);
+        let current = HashMap::from([
+            (ALICE.to_string(), 650),
+            (BOB.to_string(), 200),
+            (CAROL.to_string(), 10),
+            (DAVE.to_string(), 75),
+        ]);
+
+        assert_eq!(
+            balance_delta(&previous, &current),
+            HashMap::from([(ALICE.to_string(), 150), (BOB.to_string(), -100), (DAVE.to_string(), 75)])
+        );
+        assert!(balance_delta(&current, &current).is_empty());
+    }
+}
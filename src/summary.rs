@@ -0,0 +1,364 @@
+use std::collections::HashMap;
+
+use crate::address::{validate_address, WalletAddress};
+use crate::balance::{apply_transaction, calculate_wallet_balance, indexed_for_wallet, locate_error, OpeningBalanceGuard};
+use crate::error::TransactionError;
+use crate::transaction::{Transaction, TransactionStatus, TransactionType};
+
+/// A breakdown of a wallet's activity, suitable for building statements
+#[derive(Debug, Clone, PartialEq)]
+pub struct WalletSummary {
+    /// The resulting balance (deposits and rewards minus withdrawals and fees)
+    pub net: i64,
+    /// The sum of all deposit amounts
+    pub total_deposits: i64,
+    /// The sum of all withdrawal amounts
+    pub total_withdrawals: i64,
+    /// The sum of all staking rewards, which are not included in `total_deposits`
+    pub total_rewards: i64,
+    /// The number of deposits
+    pub deposit_count: usize,
+    /// The number of withdrawals
+    pub withdrawal_count: usize,
+}
+
+/// The outcome of checking a computed balance against an externally reported one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReconResult {
+    /// The balance calculated from the transactions
+    pub computed: i64,
+    /// The balance reported by the external source, e.g. an exchange
+    pub expected: i64,
+    /// How far the computed balance is above the expected one (`computed - expected`)
+    pub difference: i64,
+    /// Whether the two balances agree exactly
+    pub matches: bool,
+}
+
+/// Calculates a detailed summary of a wallet's native SOL activity
+///
+/// Transfers are counted from the wallet's point of view: incoming transfers as
+/// deposits and outgoing transfers as withdrawals. Failed transactions are skipped.
+/// Standalone fee transactions fit neither total and are rejected.
+///
+/// # Arguments
+///
+/// * `wallet_address` - The address of the wallet to summarize
+/// * `transactions` - A slice of transactions to process
+///
+/// # Returns
+///
+/// * `Ok(WalletSummary)` - The net balance along with deposit and withdrawal totals and counts
+/// * `Err(TransactionError)` - If there's an error processing the transactions
+///
+pub fn calculate_wallet_summary(
+    wallet_address: &str,
+    transactions: &[Transaction],
+) -> Result<WalletSummary, TransactionError> {
+    validate_address(wallet_address)?;
+    if transactions.is_empty() {
+        return Err(TransactionError::NoTransactions(wallet_address.to_string()));
+    }
+
+    let overflow = || TransactionError::BalanceOverflow(wallet_address.to_string());
+    let mut summary = WalletSummary {
+        net: 0,
+        total_deposits: 0,
+        total_withdrawals: 0,
+        total_rewards: 0,
+        deposit_count: 0,
+        withdrawal_count: 0,
+    };
+    let mut opening = OpeningBalanceGuard::default();
+    for (index, tx) in indexed_for_wallet(wallet_address, transactions).filter(|(_, tx)| tx.mint.is_none()) {
+        let locate = move |e| locate_error(index, e);
+        opening.record(tx, wallet_address).map_err(locate)?;
+        summary.net = apply_transaction(summary.net, tx, wallet_address).map_err(locate)?;
+        if tx.status == TransactionStatus::Failed {
+            continue;
+        }
+        let incoming = match &tx.transaction_type {
+            TransactionType::Deposit => true,
+            TransactionType::Withdrawal => false,
+            TransactionType::StakingReward => {
+                summary.total_rewards = summary.total_rewards.checked_add(tx.amount).ok_or_else(overflow)?;
+                continue;
+            }
+            // The seeded balance is already in the net, but is neither a deposit nor a withdrawal
+            TransactionType::OpeningBalance => continue,
+            TransactionType::Fee => {
+                return Err(TransactionError::UnsupportedTransactionType(tx.transaction_type.to_string()))
+            }
+            TransactionType::Transfer { to } => to == wallet_address,
+        };
+        if incoming {
+            summary.total_deposits = summary.total_deposits.checked_add(tx.amount).ok_or_else(overflow)?;
+            summary.deposit_count += 1;
+        } else {
+            summary.total_withdrawals = summary.total_withdrawals.checked_add(tx.amount).ok_or_else(overflow)?;
+            summary.withdrawal_count += 1;
+        }
+    }
+    Ok(summary)
+}
+
+/// Breaks a wallet's native SOL balance down by the type of transaction it came from
+///
+/// Each type maps to its signed contribution: deposits add to the balance and withdrawals
+/// subtract from it. A transaction's fee counts towards its own type, so the contributions
+/// sum to the wallet's balance. As in [`count_by_type`](crate::count_by_type), transfers to
+/// different destinations are keyed separately, which tells incoming and outgoing transfers
+/// apart. Failed transactions are skipped.
+///
+/// # Arguments
+///
+/// * `wallet` - The address of the wallet to break down
+/// * `transactions` - A slice of transactions to process
+///
+/// # Returns
+///
+/// * `Ok(HashMap<TransactionType, i64>)` - The net contribution of each type present
+/// * `Err(TransactionError)` - If the address or a transaction is invalid, or a subtotal overflows
+///
+pub fn balance_by_type(wallet: &str, transactions: &[Transaction]) -> Result<HashMap<TransactionType, i64>, TransactionError> {
+    validate_address(wallet)?;
+    let mut breakdown = HashMap::new();
+    let mut opening = OpeningBalanceGuard::default();
+    for (index, tx) in indexed_for_wallet(wallet, transactions).filter(|(_, tx)| tx.mint.is_none()) {
+        let locate = move |e| locate_error(index, e);
+        opening.record(tx, wallet).map_err(locate)?;
+        let contribution = apply_transaction(0, tx, wallet).map_err(locate)?;
+        if tx.status == TransactionStatus::Failed {
+            continue;
+        }
+        let subtotal = breakdown.entry(tx.transaction_type.clone()).or_insert(0i64);
+        *subtotal = subtotal
+            .checked_add(contribution)
+            .ok_or_else(|| TransactionError::BalanceOverflow(wallet.to_string()))?;
+    }
+    Ok(breakdown)
+}
+
+/// Reconciles a wallet's computed balance against an expected balance
+///
+/// # Arguments
+///
+/// * `wallet` - The address of the wallet to reconcile
+/// * `transactions` - A slice of transactions to process
+/// * `expected` - The balance the wallet is expected to have
+///
+/// # Returns
+///
+/// * `Ok(ReconResult)` - Both balances along with the difference between them
+/// * `Err(TransactionError)` - If there's an error processing the transactions, or the
+///   difference does not fit in an i64
+///
+pub fn reconcile(wallet: &str, transactions: &[Transaction], expected: i64) -> Result<ReconResult, TransactionError> {
+    let computed = calculate_wallet_balance(&WalletAddress::parse(wallet)?, transactions)?;
+    let difference = computed
+        .checked_sub(expected)
+        .ok_or_else(|| TransactionError::BalanceOverflow(wallet.to_string()))?;
+
+    Ok(ReconResult {
+        computed,
+        expected,
+        difference,
+        matches: difference == 0,
+    })
+}
+
+/// Checks whether two balances differ by no more than `tolerance`
+///
+/// Useful when one balance was derived from floating-point amounts. Differences too large
+/// to represent as an i64 necessarily exceed the tolerance, and a negative tolerance
+/// never matches.
+///
+/// # Arguments
+///
+/// * `a` - The first balance
+/// * `b` - The second balance
+/// * `tolerance` - The largest acceptable difference, inclusive
+///
+/// # Returns
+///
+/// * `bool` - True if `|a - b| <= tolerance`
+///
+pub fn balances_match_within(a: i64, b: i64, tolerance: i64) -> bool {
+    // unsigned_abs avoids overflowing on a difference of i64::MIN
+    match (a.checked_sub(b), u64::try_from(tolerance)) {
+        (Some(difference), Ok(tolerance)) => difference.unsigned_abs() <= tolerance,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALICE: &str = "ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3";
+    const BOB: &str = "9jLkNAaW9E47LQMHvjohy2uAAyr1331bAxgJKFRU7wF6";
+
+    /// Tests every field of the summary for a small mixed history
+    #[test]
+    fn test_calculate_wallet_summary() {
+        let transactions = vec![
+            Transaction::new(TransactionType::Deposit, ALICE, 100).unwrap(),
+            Transaction::new(TransactionType::Withdrawal, ALICE, 30).unwrap(),
+            Transaction::new(TransactionType::Deposit, BOB, 999).unwrap(),
+            Transaction::new(TransactionType::Deposit, ALICE, 50).unwrap(),
+            Transaction::new(TransactionType::Withdrawal, ALICE, 20).unwrap(),
+            Transaction::new(TransactionType::Withdrawal, ALICE, 10).unwrap(),
+        ];
+
+        let summary = calculate_wallet_summary(ALICE, &transactions).unwrap();
+        assert_eq!(
+            summary,
+            WalletSummary {
+                net: 90,
+                total_deposits: 150,
+                total_withdrawals: 60,
+                total_rewards: 0,
+                deposit_count: 2,
+                withdrawal_count: 3,
+            }
+        );
+    }
+
+    /// Tests that transfers count as deposits for the receiver and withdrawals for the sender
+    #[test]
+    fn test_wallet_summary_with_transfers() {
+        let transactions = vec![
+            Transaction::new(TransactionType::Deposit, ALICE, 100).unwrap(),
+            Transaction::new(TransactionType::Transfer { to: BOB.to_string() }, ALICE, 40).unwrap(),
+        ];
+
+        let alice = calculate_wallet_summary(ALICE, &transactions).unwrap();
+        assert_eq!((alice.net, alice.total_withdrawals, alice.withdrawal_count), (60, 40, 1));
+        let bob = calculate_wallet_summary(BOB, &transactions).unwrap();
+        assert_eq!((bob.net, bob.total_deposits, bob.deposit_count), (40, 40, 1));
+    }
+
+    /// Tests that a standalone fee transaction is rejected by the summary
+    #[test]
+    fn test_wallet_summary_rejects_fee() {
+        let transactions = vec![
+            Transaction::new(TransactionType::Deposit, ALICE, 100).unwrap(),
+            Transaction::new(TransactionType::Fee, ALICE, 5).unwrap(),
+        ];
+        assert!(matches!(
+            calculate_wallet_summary(ALICE, &transactions),
+            Err(TransactionError::UnsupportedTransactionType(_))
+        ));
+    }
+
+    /// Tests that staking rewards count towards the net but are reported apart from deposits
+    #[test]
+    fn test_wallet_summary_with_rewards() {
+        let transactions = vec![
+            Transaction::new(TransactionType::Deposit, ALICE, 1_000).unwrap(),
+            Transaction::new(TransactionType::StakingReward, ALICE, 12).unwrap(),
+            Transaction::new(TransactionType::StakingReward, ALICE, 8).unwrap(),
+            Transaction::new(TransactionType::Withdrawal, ALICE, 100).unwrap(),
+        ];
+
+        let summary = calculate_wallet_summary(ALICE, &transactions).unwrap();
+        assert_eq!(summary.net, 920);
+        assert_eq!(summary.total_deposits, 1_000);
+        assert_eq!(summary.deposit_count, 1);
+        assert_eq!(summary.total_rewards, 20);
+    }
+
+    /// Tests a reconciliation where the computed balance falls short of the expected one
+    #[test]
+    fn test_reconcile_mismatch() {
+        let transactions = vec![
+            Transaction::new(TransactionType::Deposit, ALICE, 100).unwrap(),
+            Transaction::new(TransactionType::Withdrawal, ALICE, 30).unwrap(),
+        ];
+
+        let result = reconcile(ALICE, &transactions, 95).unwrap();
+        assert_eq!(
+            result,
+            ReconResult {
+                computed: 70,
+                expected: 95,
+                difference: -25,
+                matches: false,
+            }
+        );
+        assert!(reconcile(ALICE, &transactions, 70).unwrap().matches);
+    }
+
+    /// Tests balances at, within, and outside the tolerance, including negative balances
+    #[test]
+    fn test_balances_match_within() {
+        assert!(balances_match_within(100, 105, 5));
+        assert!(balances_match_within(105, 100, 5));
+        assert!(balances_match_within(100, 102, 5));
+        assert!(!balances_match_within(100, 106, 5));
+        assert!(balances_match_within(-100, -95, 5));
+        assert!(!balances_match_within(-3, 3, 5));
+        assert!(balances_match_within(-2, 3, 5));
+        assert!(!balances_match_within(1, 1, -1));
+    }
+
+    /// Tests that extreme balances whose difference overflows never match
+    #[test]
+    fn test_balances_match_within_extremes() {
+        assert!(!balances_match_within(i64::MAX, i64::MIN, i64::MAX));
+        assert!(!balances_match_within(-1, i64::MAX, i64::MAX));
+        assert!(balances_match_within(i64::MAX, i64::MAX, 0));
+    }
+
+    /// Tests the signed deposit and withdrawal subtotals of a wallet's balance
+    #[test]
+    fn test_balance_by_type() {
+        let transactions = vec![
+            Transaction::new(TransactionType::Deposit, ALICE, 100).unwrap(),
+            Transaction::new(TransactionType::Deposit, ALICE, 50).unwrap().with_fee(5).unwrap(),
+            Transaction::new(TransactionType::Withdrawal, ALICE, 30).unwrap(),
+            Transaction::new(TransactionType::Withdrawal, ALICE, 20).unwrap(),
+            Transaction::new(TransactionType::Transfer { to: ALICE.to_string() }, BOB, 7).unwrap(),
+            Transaction::new(TransactionType::Deposit, BOB, 1_000).unwrap(),
+        ];
+
+        let breakdown = balance_by_type(ALICE, &transactions).unwrap();
+        assert_eq!(
+            breakdown,
+            HashMap::from([
+                (TransactionType::Deposit, 145),
+                (TransactionType::Withdrawal, -50),
+                (TransactionType::Transfer { to: ALICE.to_string() }, 7),
+            ])
+        );
+        assert_eq!(breakdown.values().sum::<i64>(), 102);
+    }
+
+    /// Returns a history in which Alice's opening balance follows a deposit
+    fn misplaced_opening_history() -> Vec<Transaction> {
+        vec![
+            Transaction::new(TransactionType::Deposit, ALICE, 100).unwrap(),
+            Transaction::new(TransactionType::OpeningBalance, ALICE, 1_000).unwrap(),
+        ]
+    }
+
+    /// Tests that calculate_wallet_summary rejects an opening balance after other activity
+    #[test]
+    fn test_wallet_summary_misplaced_opening_balance() {
+        assert!(matches!(
+            calculate_wallet_summary(ALICE, &misplaced_opening_history()),
+            Err(TransactionError::InvalidTransaction { index: 1, source })
+                if matches!(*source, TransactionError::MisplacedOpeningBalance(_))
+        ));
+    }
+
+    /// Tests that balance_by_type rejects an opening balance after other activity
+    #[test]
+    fn test_balance_by_type_misplaced_opening_balance() {
+        assert!(matches!(
+            balance_by_type(ALICE, &misplaced_opening_history()),
+            Err(TransactionError::InvalidTransaction { index: 1, source })
+                if matches!(*source, TransactionError::MisplacedOpeningBalance(_))
+        ));
+    }
+}
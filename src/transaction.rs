@@ -0,0 +1,629 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::address::{validate_address, validate_mint};
+use crate::error::TransactionError;
+
+/// Represents the type of transaction
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TransactionType {
+    /// Adds funds to the wallet
+    Deposit,
+    /// Removes funds from the wallet
+    Withdrawal,
+    /// Credits a staking reward, adding funds like a deposit but reported separately
+    StakingReward,
+    /// Charges a standalone network fee, always removing funds from the wallet
+    Fee,
+    /// Seeds the wallet's balance when importing it mid-history; must be its first transaction
+    OpeningBalance,
+    /// Moves funds from the wallet to the destination address `to`
+    Transfer {
+        /// The address receiving the funds
+        to: String,
+    },
+}
+
+impl FromStr for TransactionType {
+    type Err = TransactionError;
+
+    /// Parses a transaction type from its name, ignoring case
+    ///
+    /// Transfers need a destination address and cannot be parsed from a name alone.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "deposit" => Ok(TransactionType::Deposit),
+            "withdrawal" => Ok(TransactionType::Withdrawal),
+            "stakingreward" | "staking_reward" => Ok(TransactionType::StakingReward),
+            "fee" => Ok(TransactionType::Fee),
+            "openingbalance" | "opening_balance" => Ok(TransactionType::OpeningBalance),
+            _ => Err(TransactionError::UnknownTransactionType(s.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for TransactionType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TransactionType::Deposit => write!(f, "Deposit"),
+            TransactionType::Withdrawal => write!(f, "Withdrawal"),
+            TransactionType::StakingReward => write!(f, "StakingReward"),
+            TransactionType::Fee => write!(f, "Fee"),
+            TransactionType::OpeningBalance => write!(f, "OpeningBalance"),
+            TransactionType::Transfer { .. } => write!(f, "Transfer"),
+        }
+    }
+}
+
+/// Represents how far a transaction has progressed on-chain
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TransactionStatus {
+    /// Submitted but not yet confirmed; counted in the available balance only
+    Pending,
+    /// Confirmed on-chain; counted in every balance
+    #[default]
+    Confirmed,
+    /// Rejected on-chain; never counted
+    Failed,
+}
+
+/// Represents a single transaction with its associated data
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Transaction {
+    /// The type of transaction (Deposit or Withdrawal)
+    pub(crate) transaction_type: TransactionType,
+    /// The wallet address associated with the transaction
+    pub(crate) wallet_address: String,
+    /// The amount of the transaction (must be positive)
+    pub(crate) amount: i64,
+    /// The SPL token mint the amount is denominated in, or `None` for native SOL
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) mint: Option<String>,
+    /// When the transaction happened, in Unix seconds
+    #[serde(default)]
+    pub(crate) timestamp: i64,
+    /// The network fee paid by the wallet for this transaction, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) fee: Option<i64>,
+    /// The on-chain signature identifying the transaction, or empty if unknown
+    #[serde(default)]
+    pub(crate) signature: String,
+    /// Whether the transaction is pending, confirmed, or failed
+    #[serde(default)]
+    pub(crate) status: TransactionStatus,
+    /// A free-form note attached via a memo instruction, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) memo: Option<String>,
+    /// The raw ed25519 signature over `message_bytes`, base58-encoded when serialized
+    #[serde(default, skip_serializing_if = "Option::is_none", with = "base58_signature")]
+    pub(crate) signature_bytes: Option<[u8; 64]>,
+    /// The raw message the wallet signed, if known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) message_bytes: Option<Vec<u8>>,
+    /// How many blocks have been confirmed on top of the transaction's block
+    #[serde(default)]
+    pub(crate) confirmations: u32,
+}
+
+impl Transaction {
+    /// Creates a new transaction, validating its address and amount up front
+    ///
+    /// # Arguments
+    ///
+    /// * `transaction_type` - Whether the transaction adds, removes, or transfers funds
+    /// * `wallet_address` - The Solana address of the wallet (the source of a transfer)
+    /// * `amount` - The amount of the transaction (must be positive)
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Transaction)` - The validated transaction
+    /// * `Err(TransactionError)` - If the address or amount is invalid
+    ///
+    pub fn new(
+        transaction_type: TransactionType,
+        wallet_address: impl Into<String>,
+        amount: i64,
+    ) -> Result<Transaction, TransactionError> {
+        let tx = Transaction {
+            transaction_type,
+            wallet_address: wallet_address.into(),
+            amount,
+            mint: None,
+            timestamp: 0,
+            fee: None,
+            signature: String::new(),
+            status: TransactionStatus::Confirmed,
+            memo: None,
+            signature_bytes: None,
+            message_bytes: None,
+            confirmations: 0,
+        };
+        tx.validate()?;
+        Ok(tx)
+    }
+
+    /// Sets when the transaction happened, in Unix seconds
+    pub fn with_timestamp(mut self, timestamp: i64) -> Transaction {
+        self.timestamp = timestamp;
+        self
+    }
+
+    /// Sets the on-chain signature identifying the transaction
+    pub fn with_signature(mut self, signature: impl Into<String>) -> Transaction {
+        self.signature = signature.into();
+        self
+    }
+
+    /// Sets whether the transaction is pending, confirmed, or failed
+    pub fn with_status(mut self, status: TransactionStatus) -> Transaction {
+        self.status = status;
+        self
+    }
+
+    /// Sets how many blocks have been confirmed on top of the transaction's block
+    pub fn with_confirmations(mut self, confirmations: u32) -> Transaction {
+        self.confirmations = confirmations;
+        self
+    }
+
+    /// Attaches a free-form memo to the transaction
+    pub fn with_memo(mut self, memo: impl Into<String>) -> Transaction {
+        self.memo = Some(memo.into());
+        self
+    }
+
+    /// Attaches the raw ed25519 signature and the message it was made over
+    pub fn with_signed_message(mut self, signature_bytes: [u8; 64], message_bytes: impl Into<Vec<u8>>) -> Transaction {
+        self.signature_bytes = Some(signature_bytes);
+        self.message_bytes = Some(message_bytes.into());
+        self
+    }
+
+    /// Sets the network fee paid by the wallet for this transaction
+    ///
+    /// # Arguments
+    ///
+    /// * `fee` - The fee amount (must not be negative)
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Transaction)` - The transaction carrying the fee
+    /// * `Err(TransactionError)` - If the fee is negative
+    ///
+    pub fn with_fee(mut self, fee: i64) -> Result<Transaction, TransactionError> {
+        validate_fee(fee)?;
+        self.fee = Some(fee);
+        Ok(self)
+    }
+
+    /// Denominates the transaction in an SPL token instead of native SOL
+    ///
+    /// # Arguments
+    ///
+    /// * `mint` - The address of the token mint
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Transaction)` - The transaction tagged with the mint
+    /// * `Err(TransactionError)` - If the mint address is invalid
+    ///
+    pub fn with_mint(mut self, mint: impl Into<String>) -> Result<Transaction, TransactionError> {
+        let mint = mint.into();
+        validate_mint(&mint)?;
+        self.mint = Some(mint);
+        Ok(self)
+    }
+
+    /// Checks the addresses, mint, and amount of an already-built transaction,
+    /// such as one deserialized from an external source
+    pub(crate) fn validate(&self) -> Result<(), TransactionError> {
+        validate_address(&self.wallet_address)?;
+        if let TransactionType::Transfer { to } = &self.transaction_type {
+            validate_address(to)?;
+            if *to == self.wallet_address {
+                return Err(TransactionError::SelfTransfer(to.clone()));
+            }
+        }
+        if let Some(mint) = &self.mint {
+            validate_mint(mint)?;
+        }
+        if let Some(fee) = self.fee {
+            validate_fee(fee)?;
+        }
+        validate_amount(self.amount)
+    }
+
+    /// Returns true if the wallet is the source or the destination of the transaction
+    pub(crate) fn involves(&self, wallet_address: &str) -> bool {
+        self.wallet_address == wallet_address
+            || matches!(&self.transaction_type, TransactionType::Transfer { to } if to == wallet_address)
+    }
+
+    /// Returns the type of the transaction
+    pub fn transaction_type(&self) -> &TransactionType {
+        &self.transaction_type
+    }
+
+    /// Returns the wallet address associated with the transaction
+    pub fn wallet_address(&self) -> &str {
+        &self.wallet_address
+    }
+
+    /// Returns the amount of the transaction
+    pub fn amount(&self) -> i64 {
+        self.amount
+    }
+
+    /// Returns the SPL token mint of the transaction, or `None` for native SOL
+    pub fn mint(&self) -> Option<&str> {
+        self.mint.as_deref()
+    }
+
+    /// Returns when the transaction happened, in Unix seconds
+    pub fn timestamp(&self) -> i64 {
+        self.timestamp
+    }
+
+    /// Returns the network fee paid for the transaction, if any
+    pub fn fee(&self) -> Option<i64> {
+        self.fee
+    }
+
+    /// Returns the on-chain signature of the transaction, or an empty string if unknown
+    pub fn signature(&self) -> &str {
+        &self.signature
+    }
+
+    /// Returns whether the transaction is pending, confirmed, or failed
+    pub fn status(&self) -> TransactionStatus {
+        self.status
+    }
+
+    /// Returns how many blocks have been confirmed on top of the transaction's block
+    pub fn confirmations(&self) -> u32 {
+        self.confirmations
+    }
+
+    /// Returns the memo attached to the transaction, if any
+    pub fn memo(&self) -> Option<&str> {
+        self.memo.as_deref()
+    }
+
+    /// Returns the raw ed25519 signature of the transaction, if any
+    pub fn signature_bytes(&self) -> Option<&[u8; 64]> {
+        self.signature_bytes.as_ref()
+    }
+
+    /// Returns the raw message the wallet signed, if any
+    pub fn message_bytes(&self) -> Option<&[u8]> {
+        self.message_bytes.as_deref()
+    }
+}
+
+impl fmt::Display for Transaction {
+    /// Formats the transaction as e.g. `Deposit 100 -> ALiC...Nqp3`, with the arrow
+    /// showing the direction funds move relative to the (truncated) wallet address
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let wallet = short_address(&self.wallet_address);
+        match &self.transaction_type {
+            TransactionType::Deposit => write!(f, "Deposit {} -> {}", self.amount, wallet),
+            TransactionType::Withdrawal => write!(f, "Withdrawal {} <- {}", self.amount, wallet),
+            TransactionType::StakingReward => write!(f, "StakingReward {} -> {}", self.amount, wallet),
+            TransactionType::Fee => write!(f, "Fee {} <- {}", self.amount, wallet),
+            TransactionType::OpeningBalance => write!(f, "OpeningBalance {} -> {}", self.amount, wallet),
+            TransactionType::Transfer { to } => {
+                write!(f, "Transfer {} {} -> {}", self.amount, wallet, short_address(to))
+            }
+        }
+    }
+}
+
+impl TryFrom<&str> for Transaction {
+    type Error = TransactionError;
+
+    /// Parses a compact `type:address:amount` string such as `deposit:ALiCE...:100`
+    ///
+    /// Each part is validated as it would be by [`Transaction::new`].
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        let parts: Vec<&str> = s.split(':').collect();
+        let [transaction_type, wallet_address, amount] = parts[..] else {
+            return Err(TransactionError::ParseError(format!(
+                "expected type:address:amount, got {:?}",
+                s
+            )));
+        };
+
+        let transaction_type = transaction_type.trim().parse()?;
+        let amount = parse_amount(amount)?;
+        Transaction::new(transaction_type, wallet_address.trim(), amount)
+    }
+}
+
+/// Serializes a raw 64-byte signature as a base58 string, as Solana displays signatures
+mod base58_signature {
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub(super) fn serialize<S: Serializer>(bytes: &Option<[u8; 64]>, serializer: S) -> Result<S::Ok, S::Error> {
+        match bytes {
+            Some(bytes) => serializer.serialize_some(&bs58::encode(bytes).into_string()),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<[u8; 64]>, D::Error> {
+        let Some(encoded) = Option::<String>::deserialize(deserializer)? else {
+            return Ok(None);
+        };
+        let decoded = bs58::decode(&encoded).into_vec().map_err(D::Error::custom)?;
+        let bytes = decoded
+            .try_into()
+            .map_err(|_| D::Error::custom("signature must be 64 bytes"))?;
+        Ok(Some(bytes))
+    }
+}
+
+/// Truncates an address to its first and last four characters for display
+fn short_address(address: &str) -> String {
+    if address.len() <= 11 || !address.is_ascii() {
+        return address.to_string();
+    }
+    format!("{}...{}", &address[..4], &address[address.len() - 4..])
+}
+
+/// Parses an amount from text, tolerating surrounding whitespace and thousands separators
+///
+/// Commas must group digits in threes, as in `"1,000,000"`; a misplaced separator such as
+/// in `"1,00"` is rejected rather than guessed at. The sign of the amount is kept, so
+/// range checks are left to the caller.
+///
+/// # Arguments
+///
+/// * `s` - The text to parse, e.g. `" 1,000 "`
+///
+/// # Returns
+///
+/// * `Ok(i64)` - The parsed amount
+/// * `Err(TransactionError)` - `ParseError` for a misplaced separator, or `Parse` if the
+///   text is not an integer
+///
+pub fn parse_amount(s: &str) -> Result<i64, TransactionError> {
+    let trimmed = s.trim();
+    if trimmed.contains(',') {
+        let digits = trimmed.strip_prefix(['-', '+']).unwrap_or(trimmed);
+        let mut groups = digits.split(',');
+        let leading = groups.next().unwrap_or_default();
+        if leading.is_empty() || leading.len() > 3 || groups.any(|group| group.len() != 3) {
+            return Err(TransactionError::ParseError(format!("misplaced thousands separator in {:?}", s)));
+        }
+    }
+    Ok(trimmed.replace(',', "").parse()?)
+}
+
+/// Validates that a transaction amount is strictly positive
+pub(crate) fn validate_amount(amount: i64) -> Result<(), TransactionError> {
+    if amount == 0 {
+        return Err(TransactionError::ZeroAmount);
+    }
+    if amount < 0 {
+        return Err(TransactionError::NegativeAmount(amount));
+    }
+    Ok(())
+}
+
+/// Validates that a transaction fee is not negative
+pub(crate) fn validate_fee(fee: i64) -> Result<(), TransactionError> {
+    if fee < 0 {
+        return Err(TransactionError::NegativeFee(fee));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests parsing a capitalized deposit type
+    #[test]
+    fn test_parse_deposit() {
+        assert_eq!("Deposit".parse::<TransactionType>().unwrap(), TransactionType::Deposit);
+    }
+
+    /// Tests parsing an uppercase withdrawal type
+    #[test]
+    fn test_parse_withdrawal() {
+        assert_eq!("WITHDRAWAL".parse::<TransactionType>().unwrap(), TransactionType::Withdrawal);
+    }
+
+    /// Tests parsing a fee type
+    #[test]
+    fn test_parse_fee() {
+        assert_eq!("fee".parse::<TransactionType>().unwrap(), TransactionType::Fee);
+    }
+
+    /// Tests error handling for an unknown transaction type
+    #[test]
+    fn test_parse_unknown_type() {
+        assert!(matches!(
+            "refund".parse::<TransactionType>(),
+            Err(TransactionError::UnknownTransactionType(s)) if s == "refund"
+        ));
+    }
+
+    /// Tests constructing a valid transaction
+    #[test]
+    fn test_new_transaction() {
+        let tx = Transaction::new(
+            TransactionType::Deposit,
+            "ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3",
+            100,
+        )
+        .unwrap();
+        assert_eq!(tx.transaction_type(), &TransactionType::Deposit);
+        assert_eq!(tx.wallet_address(), "ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3");
+        assert_eq!(tx.amount(), 100);
+    }
+
+    /// Tests that construction fails for an invalid address
+    #[test]
+    fn test_new_transaction_invalid_address() {
+        assert!(matches!(
+            Transaction::new(TransactionType::Deposit, "not-a-wallet", 100),
+            Err(TransactionError::InvalidWalletAddress(_))
+        ));
+    }
+
+    /// Tests that construction fails for a zero amount
+    #[test]
+    fn test_new_transaction_zero_amount() {
+        assert!(matches!(
+            Transaction::new(
+                TransactionType::Withdrawal,
+                "ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3",
+                0
+            ),
+            Err(TransactionError::ZeroAmount)
+        ));
+    }
+
+    /// Tests that a transfer to an invalid destination is rejected
+    #[test]
+    fn test_new_transfer_invalid_destination() {
+        assert!(matches!(
+            Transaction::new(
+                TransactionType::Transfer { to: "not-a-wallet".to_string() },
+                "ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3",
+                10
+            ),
+            Err(TransactionError::InvalidWalletAddress(_))
+        ));
+    }
+
+    /// Tests that a negative fee is rejected
+    #[test]
+    fn test_with_negative_fee() {
+        let tx = Transaction::new(
+            TransactionType::Deposit,
+            "ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3",
+            100,
+        )
+        .unwrap();
+        assert!(matches!(tx.with_fee(-5), Err(TransactionError::NegativeFee(-5))));
+    }
+
+    /// Tests that an invalid mint address is rejected
+    #[test]
+    fn test_with_invalid_mint() {
+        let tx = Transaction::new(
+            TransactionType::Deposit,
+            "ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3",
+            100,
+        )
+        .unwrap();
+        assert!(matches!(
+            tx.with_mint("not-a-mint"),
+            Err(TransactionError::InvalidMintAddress(_))
+        ));
+    }
+
+    /// Tests that transactions round-trip through JSON unchanged
+    #[test]
+    fn test_json_round_trip() {
+        let transactions = vec![
+            Transaction::new(
+                TransactionType::Deposit,
+                "ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3",
+                100,
+            )
+            .unwrap(),
+            Transaction::new(
+                TransactionType::Withdrawal,
+                "ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3",
+                40,
+            )
+            .unwrap()
+            .with_mint("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v")
+            .unwrap(),
+        ];
+
+        let json = serde_json::to_string(&transactions).unwrap();
+        assert!(json.contains(r#""transaction_type":"deposit""#));
+        assert!(json.contains(r#""transaction_type":"withdrawal""#));
+        let parsed: Vec<Transaction> = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, transactions);
+    }
+
+    /// Tests the display format of a deposit and a withdrawal
+    #[test]
+    fn test_display_transaction() {
+        let deposit = Transaction::new(
+            TransactionType::Deposit,
+            "ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3",
+            100,
+        )
+        .unwrap();
+        let withdrawal = Transaction::new(
+            TransactionType::Withdrawal,
+            "ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3",
+            50,
+        )
+        .unwrap();
+
+        assert_eq!(deposit.to_string(), "Deposit 100 -> ALiC...Nqp3");
+        assert_eq!(withdrawal.to_string(), "Withdrawal 50 <- ALiC...Nqp3");
+        assert_eq!(TransactionType::Deposit.to_string(), "Deposit");
+        assert_eq!(TransactionType::Withdrawal.to_string(), "Withdrawal");
+    }
+
+    /// Tests parsing a well-formed compact transaction string
+    #[test]
+    fn test_try_from_str() {
+        let tx = Transaction::try_from("deposit:ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3:100").unwrap();
+        assert_eq!(tx.transaction_type(), &TransactionType::Deposit);
+        assert_eq!(tx.wallet_address(), "ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3");
+        assert_eq!(tx.amount(), 100);
+    }
+
+    /// Tests that a compact string with a missing field is rejected
+    #[test]
+    fn test_try_from_str_missing_field() {
+        assert!(matches!(
+            Transaction::try_from("deposit:ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3"),
+            Err(TransactionError::ParseError(_))
+        ));
+    }
+
+    /// Tests that a compact string with a non-numeric amount is rejected, keeping the parse error as its source
+    #[test]
+    fn test_try_from_str_bad_amount() {
+        use std::error::Error;
+
+        let error = Transaction::try_from("withdrawal:ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3:ten").unwrap_err();
+        assert!(matches!(error, TransactionError::Parse(_)));
+        assert!(error.source().unwrap().downcast_ref::<std::num::ParseIntError>().is_some());
+    }
+
+    /// Tests parsing amounts with thousands separators and surrounding whitespace
+    #[test]
+    fn test_parse_amount() {
+        assert_eq!(parse_amount("1,000").unwrap(), 1_000);
+        assert_eq!(parse_amount(" 50 ").unwrap(), 50);
+        assert_eq!(parse_amount("-12,345,678").unwrap(), -12_345_678);
+        assert!(matches!(parse_amount("abc"), Err(TransactionError::Parse(_))));
+        assert!(matches!(parse_amount("1,00"), Err(TransactionError::ParseError(_))));
+        assert!(matches!(parse_amount(",100"), Err(TransactionError::ParseError(_))));
+    }
+
+    /// Tests that a compact string with an unknown type is rejected
+    #[test]
+    fn test_try_from_str_bad_type() {
+        assert!(matches!(
+            Transaction::try_from("refund:ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3:100"),
+            Err(TransactionError::UnknownTransactionType(_))
+        ));
+    }
+}
@@ -0,0 +1,274 @@
+use crate::error::TransactionError;
+
+/// The number of lamports in one SOL
+pub const LAMPORTS_PER_SOL: i64 = 1_000_000_000;
+
+/// Converts a lamport amount into SOL
+///
+/// # Arguments
+///
+/// * `lamports` - The amount in lamports
+///
+/// # Returns
+///
+/// * `f64` - The equivalent amount in SOL
+///
+pub fn lamports_to_sol(lamports: i64) -> f64 {
+    lamports as f64 / LAMPORTS_PER_SOL as f64
+}
+
+/// Converts a SOL amount into lamports
+///
+/// The amount must correspond to a whole number of lamports: the nearest lamport value is
+/// accepted only if converting it back yields exactly the same SOL amount.
+///
+/// # Arguments
+///
+/// * `sol` - The amount in SOL
+///
+/// # Returns
+///
+/// * `Ok(i64)` - The equivalent amount in lamports
+/// * `Err(TransactionError)` - If the amount is not finite, has a fractional lamport part,
+///   or does not fit in an i64
+///
+pub fn sol_to_lamports(sol: f64) -> Result<i64, TransactionError> {
+    let lamports = (sol * LAMPORTS_PER_SOL as f64).round();
+
+    // i64::MAX is not representable as an f64, so anything at or above 2^63 overflows
+    if !lamports.is_finite() || lamports >= i64::MAX as f64 || lamports < i64::MIN as f64 {
+        return Err(TransactionError::InvalidSolAmount(sol));
+    }
+
+    // Reject amounts that would lose a fraction of a lamport
+    if lamports / LAMPORTS_PER_SOL as f64 != sol {
+        return Err(TransactionError::InvalidSolAmount(sol));
+    }
+    Ok(lamports as i64)
+}
+
+/// The number of decimal places in one SOL's worth of lamports
+const SOL_DECIMALS: usize = 9;
+
+/// Formats a lamport amount as SOL rounded to a fixed number of decimal places
+///
+/// Rounding is done on the integer lamport amount, half away from zero, so there is no
+/// floating-point error: `1_234_567_891` lamports is `1.23` SOL at 2 decimals and
+/// `1.2346` at 4. Asking for more than 9 decimals pads with zeros. A negative amount gets
+/// a leading minus unless it rounds to zero.
+///
+/// # Arguments
+///
+/// * `lamports` - The amount in lamports
+/// * `decimals` - How many decimal places to show
+///
+/// # Returns
+///
+/// * `String` - The amount in SOL with exactly `decimals` decimal places
+///
+pub fn format_sol(lamports: i64, decimals: usize) -> String {
+    let magnitude = lamports.unsigned_abs();
+    let shown = decimals.min(SOL_DECIMALS);
+
+    // Drop the hidden lamport digits, rounding half up; the magnitude is at most 2^63, so this cannot overflow
+    let divisor = 10u64.pow((SOL_DECIMALS - shown) as u32);
+    let rounded = (magnitude + divisor / 2) / divisor;
+
+    let scale = 10u64.pow(shown as u32);
+    let sign = if lamports < 0 && rounded > 0 { "-" } else { "" };
+    let whole = rounded / scale;
+    if decimals == 0 {
+        return format!("{}{}", sign, whole);
+    }
+    format!(
+        "{}{}.{:0>shown$}{}",
+        sign,
+        whole,
+        rounded % scale,
+        "0".repeat(decimals - shown),
+        shown = shown
+    )
+}
+
+/// Display metadata for an SPL token mint
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenInfo {
+    /// The mint address of the token
+    pub mint: String,
+    /// How many decimal places the raw amount is scaled by (6 for USDC)
+    pub decimals: u8,
+}
+
+/// Formats a raw token amount as a decimal string using the mint's decimal count
+///
+/// Every decimal place is kept, so USDC (6 decimals) formats `1_500_000` as `1.500000`
+/// and `42` as `0.000042`. A token with no decimals is formatted as a plain integer.
+///
+/// # Arguments
+///
+/// * `raw` - The amount in the token's smallest unit
+/// * `info` - The token's display metadata
+///
+/// # Returns
+///
+/// * `String` - The scaled amount, with a leading `-` for negative amounts
+///
+pub fn format_token_amount(raw: i64, info: &TokenInfo) -> String {
+    let sign = if raw < 0 { "-" } else { "" };
+    let decimals = usize::from(info.decimals);
+    if decimals == 0 {
+        return format!("{}{}", sign, raw.unsigned_abs());
+    }
+
+    // Pad so there is always at least one digit before the decimal point
+    let digits = format!("{:0>width$}", raw.unsigned_abs(), width = decimals + 1);
+    let (whole, fraction) = digits.split_at(digits.len() - decimals);
+    format!("{}{}.{}", sign, whole, fraction)
+}
+
+/// Converts a lamport amount into US dollars at a given SOL price
+///
+/// A price of zero, or one that is negative or not finite, has no meaningful conversion
+/// and yields `0.0` rather than a signed zero or NaN.
+///
+/// # Arguments
+///
+/// * `lamports` - The amount in lamports
+/// * `sol_price_usd` - The price of one SOL in US dollars
+///
+/// # Returns
+///
+/// * `f64` - The equivalent amount in US dollars
+///
+pub fn balance_in_usd(lamports: i64, sol_price_usd: f64) -> f64 {
+    if !(sol_price_usd.is_finite() && sol_price_usd > 0.0) {
+        return 0.0;
+    }
+    lamports_to_sol(lamports) * sol_price_usd
+}
+
+/// A balance shown in lamports, SOL, and US dollars
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MultiCurrencyBalance {
+    /// The balance in lamports
+    pub lamports: i64,
+    /// The balance in SOL
+    pub sol: f64,
+    /// The balance in US dollars, or `0.0` if no valid price was given
+    pub usd: f64,
+}
+
+/// Reports a lamport balance in SOL and in US dollars at a given SOL price
+///
+/// # Arguments
+///
+/// * `lamports` - The balance in lamports
+/// * `sol_price_usd` - The price of one SOL in US dollars, as for [`balance_in_usd`]
+///
+/// # Returns
+///
+/// * `MultiCurrencyBalance` - The balance in each currency
+///
+pub fn report_balance(lamports: i64, sol_price_usd: f64) -> MultiCurrencyBalance {
+    MultiCurrencyBalance {
+        lamports,
+        sol: lamports_to_sol(lamports),
+        usd: balance_in_usd(lamports, sol_price_usd),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests exact conversions in both directions
+    #[test]
+    fn test_exact_conversions() {
+        assert_eq!(lamports_to_sol(1_000_000_000), 1.0);
+        assert_eq!(lamports_to_sol(2_500_000_000), 2.5);
+        assert_eq!(lamports_to_sol(-75), -0.000000075);
+        assert_eq!(sol_to_lamports(1.0).unwrap(), 1_000_000_000);
+        assert_eq!(sol_to_lamports(1.1).unwrap(), 1_100_000_000);
+        assert_eq!(sol_to_lamports(0.000000001).unwrap(), 1);
+    }
+
+    /// Tests that fractional lamport amounts are rejected
+    #[test]
+    fn test_reject_fractional_lamports() {
+        assert!(matches!(
+            sol_to_lamports(1.0000000001),
+            Err(TransactionError::InvalidSolAmount(_))
+        ));
+    }
+
+    /// Tests that amounts outside the i64 range are rejected
+    #[test]
+    fn test_reject_overflowing_sol() {
+        assert!(matches!(sol_to_lamports(1e10), Err(TransactionError::InvalidSolAmount(_))));
+        assert!(matches!(sol_to_lamports(f64::NAN), Err(TransactionError::InvalidSolAmount(_))));
+    }
+
+    /// Tests rounding a non-round lamport amount to 2, 4, and 9 decimals
+    #[test]
+    fn test_format_sol() {
+        assert_eq!(format_sol(1_234_567_891, 2), "1.23");
+        assert_eq!(format_sol(1_234_567_891, 4), "1.2346");
+        assert_eq!(format_sol(1_234_567_891, 9), "1.234567891");
+        assert_eq!(format_sol(1_234_567_891, 0), "1");
+        assert_eq!(format_sol(1_234_567_891, 11), "1.23456789100");
+    }
+
+    /// Tests half-up rounding and the sign of negative amounts
+    #[test]
+    fn test_format_sol_rounding_and_sign() {
+        assert_eq!(format_sol(1_005_000_000, 2), "1.01");
+        assert_eq!(format_sol(1_004_999_999, 2), "1.00");
+        assert_eq!(format_sol(999_999_999, 2), "1.00");
+        assert_eq!(format_sol(-1_234_567_891, 4), "-1.2346");
+        assert_eq!(format_sol(-1_000, 2), "0.00");
+        assert_eq!(format_sol(i64::MIN, 2), "-9223372036.85");
+    }
+
+    /// Tests formatting USDC-style amounts with 6 decimals, including padded small amounts
+    #[test]
+    fn test_format_token_amount_six_decimals() {
+        let usdc = TokenInfo {
+            mint: "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v".to_string(),
+            decimals: 6,
+        };
+        assert_eq!(format_token_amount(1_500_000, &usdc), "1.500000");
+        assert_eq!(format_token_amount(123_456_789, &usdc), "123.456789");
+        assert_eq!(format_token_amount(42, &usdc), "0.000042");
+        assert_eq!(format_token_amount(0, &usdc), "0.000000");
+        assert_eq!(format_token_amount(-42, &usdc), "-0.000042");
+    }
+
+    /// Tests formatting a token with no decimal places
+    #[test]
+    fn test_format_token_amount_zero_decimals() {
+        let token = TokenInfo {
+            mint: "DezXAZ8z7PnrnRJjz3wXBoRgixCa6xjnB7YaB1pPB263".to_string(),
+            decimals: 0,
+        };
+        assert_eq!(format_token_amount(1_234, &token), "1234");
+        assert_eq!(format_token_amount(-7, &token), "-7");
+    }
+
+    /// Tests reporting a balance at a known price, and at a zero price
+    #[test]
+    fn test_report_balance() {
+        assert_eq!(
+            report_balance(2_500_000_000, 150.0),
+            MultiCurrencyBalance {
+                lamports: 2_500_000_000,
+                sol: 2.5,
+                usd: 375.0,
+            }
+        );
+
+        let unpriced = report_balance(-1_000_000_000, 0.0);
+        assert_eq!(unpriced.sol, -1.0);
+        assert!(unpriced.usd == 0.0 && unpriced.usd.is_sign_positive());
+        assert_eq!(balance_in_usd(1_000_000_000, f64::NAN), 0.0);
+    }
+}
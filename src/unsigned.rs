@@ -0,0 +1,169 @@
+use crate::address::{normalize_wallet_address, validate_address};
+use crate::error::TransactionError;
+use crate::transaction::TransactionType;
+
+/// A native SOL transaction whose amount uses the full unsigned 64-bit lamport range
+///
+/// On-chain lamport amounts are `u64`, so amounts above `i64::MAX` cannot be stored in a
+/// [`Transaction`](crate::Transaction). This type carries them for
+/// [`calculate_wallet_balance_u64`]. Mints, fees, and statuses are not tracked.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnsignedTransaction {
+    /// Whether the transaction adds, removes, or transfers funds
+    transaction_type: TransactionType,
+    /// The wallet address associated with the transaction (the source of a transfer)
+    wallet_address: String,
+    /// The amount of the transaction, in lamports (must be non-zero)
+    amount: u64,
+}
+
+impl UnsignedTransaction {
+    /// Creates a new transaction, validating its addresses and amount up front
+    ///
+    /// # Arguments
+    ///
+    /// * `transaction_type` - Whether the transaction adds, removes, or transfers funds
+    /// * `wallet_address` - The Solana address of the wallet (the source of a transfer)
+    /// * `amount` - The amount of the transaction, in lamports (must be non-zero)
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(UnsignedTransaction)` - The validated transaction
+    /// * `Err(TransactionError)` - If an address is invalid or the amount is zero
+    ///
+    pub fn new(
+        transaction_type: TransactionType,
+        wallet_address: impl Into<String>,
+        amount: u64,
+    ) -> Result<UnsignedTransaction, TransactionError> {
+        let wallet_address = wallet_address.into();
+        validate_address(&wallet_address)?;
+        if let TransactionType::Transfer { to } = &transaction_type {
+            validate_address(to)?;
+            if *to == wallet_address {
+                return Err(TransactionError::SelfTransfer(to.clone()));
+            }
+        }
+        if amount == 0 {
+            return Err(TransactionError::ZeroAmount);
+        }
+        Ok(UnsignedTransaction {
+            transaction_type,
+            wallet_address,
+            amount,
+        })
+    }
+
+    /// Returns the type of the transaction
+    pub fn transaction_type(&self) -> &TransactionType {
+        &self.transaction_type
+    }
+
+    /// Returns the wallet address associated with the transaction
+    pub fn wallet_address(&self) -> &str {
+        &self.wallet_address
+    }
+
+    /// Returns the amount of the transaction, in lamports
+    pub fn amount(&self) -> u64 {
+        self.amount
+    }
+}
+
+/// Calculates the balance of a wallet from transactions with unsigned 64-bit amounts
+///
+/// The balance is an `i128`, which holds any difference between sums of `u64` amounts,
+/// so withdrawals exceeding deposits give a negative balance rather than underflowing.
+///
+/// # Arguments
+///
+/// * `wallet_address` - The address of the wallet to calculate the balance for
+/// * `transactions` - A slice of transactions to process
+///
+/// # Returns
+///
+/// * `Ok(i128)` - The calculated balance if successful
+/// * `Err(TransactionError)` - If the address is invalid, no transaction involves the
+///   wallet, or the balance overflows
+///
+pub fn calculate_wallet_balance_u64(
+    wallet_address: &str,
+    transactions: &[UnsignedTransaction],
+) -> Result<i128, TransactionError> {
+    let wallet_address = normalize_wallet_address(wallet_address)?;
+
+    let mut seen_any = false;
+    let balance = transactions
+        .iter()
+        .filter(|tx| {
+            tx.wallet_address == wallet_address
+                || matches!(&tx.transaction_type, TransactionType::Transfer { to } if *to == wallet_address)
+        })
+        .inspect(|_| seen_any = true)
+        .enumerate()
+        .try_fold(0i128, |acc, (position, tx)| {
+            let amount = i128::from(tx.amount);
+            let updated = match &tx.transaction_type {
+                TransactionType::Deposit | TransactionType::StakingReward => acc.checked_add(amount),
+                TransactionType::Withdrawal | TransactionType::Fee => acc.checked_sub(amount),
+                TransactionType::OpeningBalance if position == 0 => Some(amount),
+                TransactionType::OpeningBalance => {
+                    return Err(TransactionError::MisplacedOpeningBalance(wallet_address.clone()))
+                }
+                TransactionType::Transfer { .. } if tx.wallet_address == wallet_address => acc.checked_sub(amount),
+                TransactionType::Transfer { .. } => acc.checked_add(amount),
+            };
+            updated.ok_or_else(|| TransactionError::BalanceOverflow(wallet_address.clone()))
+        })?;
+
+    // Check if any transactions involve the wallet
+    if !seen_any {
+        return Err(TransactionError::NoTransactions(wallet_address));
+    }
+    Ok(balance)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALICE: &str = "ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3";
+    const BOB: &str = "9jLkNAaW9E47LQMHvjohy2uAAyr1331bAxgJKFRU7wF6";
+
+    /// Tests deposits above i64::MAX, which a signed amount cannot represent
+    #[test]
+    fn test_amounts_above_i64_max() {
+        let transactions = vec![
+            UnsignedTransaction::new(TransactionType::Deposit, ALICE, u64::MAX).unwrap(),
+            UnsignedTransaction::new(TransactionType::Deposit, ALICE, u64::MAX).unwrap(),
+            UnsignedTransaction::new(TransactionType::Withdrawal, ALICE, 1).unwrap(),
+        ];
+        assert_eq!(
+            calculate_wallet_balance_u64(ALICE, &transactions).unwrap(),
+            2 * i128::from(u64::MAX) - 1
+        );
+    }
+
+    /// Tests that withdrawals exceeding deposits go negative instead of underflowing
+    #[test]
+    fn test_withdrawals_exceed_deposits() {
+        let transactions = vec![
+            UnsignedTransaction::new(TransactionType::Deposit, ALICE, 1).unwrap(),
+            UnsignedTransaction::new(TransactionType::Transfer { to: BOB.to_string() }, ALICE, u64::MAX).unwrap(),
+        ];
+        assert_eq!(
+            calculate_wallet_balance_u64(ALICE, &transactions).unwrap(),
+            1 - i128::from(u64::MAX)
+        );
+        assert_eq!(calculate_wallet_balance_u64(BOB, &transactions).unwrap(), i128::from(u64::MAX));
+    }
+
+    /// Tests that a zero amount is rejected
+    #[test]
+    fn test_zero_unsigned_amount() {
+        assert!(matches!(
+            UnsignedTransaction::new(TransactionType::Deposit, ALICE, 0),
+            Err(TransactionError::ZeroAmount)
+        ));
+    }
+}
@@ -0,0 +1,122 @@
+use crate::address::normalize_wallet_address;
+use crate::balance::apply_transaction;
+use crate::error::TransactionError;
+use crate::transaction::{Transaction, TransactionType};
+
+/// A wallet's native SOL balance, updated in place one transaction at a time
+///
+/// A long-running service can keep one per wallet and apply transactions as they arrive
+/// instead of recalculating from the full history.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Wallet {
+    /// The address of the wallet
+    address: String,
+    /// The current native balance
+    balance: i64,
+    /// Whether any transaction has been applied yet, which rules out an opening balance
+    active: bool,
+}
+
+impl Wallet {
+    /// Creates a wallet with a balance of zero
+    ///
+    /// # Arguments
+    ///
+    /// * `address` - The address of the wallet, trimmed of surrounding whitespace
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Wallet)` - The empty wallet
+    /// * `Err(TransactionError)` - If the address is empty or not a valid Solana address
+    ///
+    pub fn new(address: &str) -> Result<Wallet, TransactionError> {
+        Ok(Wallet {
+            address: normalize_wallet_address(address)?,
+            balance: 0,
+            active: false,
+        })
+    }
+
+    /// Returns the address of the wallet
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+
+    /// Returns the current native balance of the wallet
+    pub fn balance(&self) -> i64 {
+        self.balance
+    }
+
+    /// Validates a transaction and applies it to the balance
+    ///
+    /// SPL token transactions are validated but leave the native balance unchanged. On
+    /// error the wallet is left as it was.
+    ///
+    /// # Arguments
+    ///
+    /// * `tx` - The transaction to apply
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the transaction was applied
+    /// * `Err(TransactionError)` - `WalletMismatch` if the transaction does not involve this
+    ///   wallet, `MisplacedOpeningBalance` for an opening balance after other activity, or
+    ///   any error from validating or applying the transaction
+    ///
+    pub fn apply(&mut self, tx: &Transaction) -> Result<(), TransactionError> {
+        tx.validate()?;
+        if !tx.involves(&self.address) {
+            return Err(TransactionError::WalletMismatch {
+                wallet: self.address.clone(),
+                transaction_wallet: tx.wallet_address.clone(),
+            });
+        }
+        if self.active && tx.transaction_type == TransactionType::OpeningBalance {
+            return Err(TransactionError::MisplacedOpeningBalance(self.address.clone()));
+        }
+
+        if tx.mint.is_none() {
+            self.balance = apply_transaction(self.balance, tx, &self.address)?;
+        }
+        self.active = true;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALICE: &str = "ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3";
+    const BOB: &str = "9jLkNAaW9E47LQMHvjohy2uAAyr1331bAxgJKFRU7wF6";
+
+    /// Tests applying a sequence of transactions and reading back the final balance
+    #[test]
+    fn test_apply_sequence() {
+        let mut wallet = Wallet::new(ALICE).unwrap();
+        let transactions = [
+            Transaction::new(TransactionType::Deposit, ALICE, 100).unwrap(),
+            Transaction::new(TransactionType::Withdrawal, ALICE, 30).unwrap().with_fee(2).unwrap(),
+            Transaction::new(TransactionType::Transfer { to: ALICE.to_string() }, BOB, 15).unwrap(),
+            Transaction::new(TransactionType::Transfer { to: BOB.to_string() }, ALICE, 10).unwrap(),
+        ];
+        for tx in &transactions {
+            wallet.apply(tx).unwrap();
+        }
+        assert_eq!(wallet.balance(), 73);
+    }
+
+    /// Tests that a transaction for another wallet is rejected without changing the balance
+    #[test]
+    fn test_apply_other_wallet() {
+        let mut wallet = Wallet::new(ALICE).unwrap();
+        wallet.apply(&Transaction::new(TransactionType::Deposit, ALICE, 50).unwrap()).unwrap();
+
+        let result = wallet.apply(&Transaction::new(TransactionType::Deposit, BOB, 100).unwrap());
+        assert!(matches!(
+            result,
+            Err(TransactionError::WalletMismatch { transaction_wallet, .. }) if transaction_wallet == BOB
+        ));
+        assert_eq!(wallet.balance(), 50);
+    }
+}
@@ -0,0 +1,55 @@
+use wasm_bindgen::prelude::*;
+
+use crate::address::WalletAddress;
+use crate::balance::calculate_wallet_balance;
+use crate::error::TransactionError;
+use crate::import::parse_transactions_json;
+
+/// Calculates a wallet's balance from a JSON array of transactions, for use from JavaScript
+///
+/// Every failure is returned to the caller as an exception carrying the error message; the
+/// calculation never panics.
+///
+/// # Arguments
+///
+/// * `wallet` - The address of the wallet to calculate the balance for
+/// * `json` - The transaction history, as a JSON array of transactions
+///
+/// # Returns
+///
+/// * `Ok(i64)` - The calculated balance, a `BigInt` in JavaScript
+/// * `Err(JsValue)` - The error message if the address, JSON, or any transaction is invalid
+///
+#[wasm_bindgen]
+pub fn wasm_calculate_balance(wallet: &str, json: &str) -> Result<i64, JsValue> {
+    balance_from_json(wallet, json).map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+/// Parses the history and calculates the balance, keeping the Rust error for native callers
+fn balance_from_json(wallet: &str, json: &str) -> Result<i64, TransactionError> {
+    let transactions = parse_transactions_json(json)?;
+    calculate_wallet_balance(&WalletAddress::parse(wallet)?, &transactions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALICE: &str = "ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3";
+
+    /// Tests the JSON path behind the wasm entry point, including its error cases
+    #[test]
+    fn test_balance_from_json() {
+        let json = r#"[
+            {"transaction_type": "deposit", "wallet_address": "ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3", "amount": 100},
+            {"transaction_type": "withdrawal", "wallet_address": "ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3", "amount": 40}
+        ]"#;
+
+        assert_eq!(balance_from_json(ALICE, json).unwrap(), 60);
+        assert!(matches!(balance_from_json(ALICE, "not json"), Err(TransactionError::Json(_))));
+        assert!(matches!(
+            balance_from_json("not-a-wallet", json),
+            Err(TransactionError::InvalidWalletAddress(_))
+        ));
+    }
+}
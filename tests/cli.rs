@@ -0,0 +1,91 @@
+//! Integration tests for the binary's command-line arguments.
+
+use std::fs;
+use std::process::{Command, Stdio};
+
+/// Runs the binary with `args`, returning its exit code and stdout
+fn run_with_args(args: &[&str]) -> (Option<i32>, String) {
+    let output = Command::new(env!("CARGO_BIN_EXE_ryz-coding-challenge"))
+        .args(args)
+        .env_remove("SOLANA_CLUSTER")
+        .stdin(Stdio::null())
+        .output()
+        .unwrap();
+    (output.status.code(), String::from_utf8(output.stdout).unwrap())
+}
+
+/// Tests that a JSON input file produces a balance per wallet, or one wallet's balance when selected
+#[test]
+fn test_balances_from_json_file() {
+    let path = std::env::temp_dir().join("ryz_cli_balances_from_json_file.json");
+    fs::write(
+        &path,
+        r#"[
+            {"transaction_type": "deposit", "wallet_address": "ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3", "amount": 100},
+            {"transaction_type": "deposit", "wallet_address": "9jLkNAaW9E47LQMHvjohy2uAAyr1331bAxgJKFRU7wF6", "amount": 250}
+        ]"#,
+    )
+    .unwrap();
+    let input = path.to_str().unwrap();
+
+    let (code, stdout) = run_with_args(&["--input", input]);
+    assert_eq!(code, Some(0));
+    assert_eq!(
+        stdout,
+        "Cluster: mainnet\n\
+         9jLkNAaW9E47LQMHvjohy2uAAyr1331bAxgJKFRU7wF6: 250 lamports (0.00000025 SOL)\n\
+         ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3: 100 lamports (0.0000001 SOL)\n"
+    );
+
+    let (code, stdout) = run_with_args(&[
+        "--input",
+        input,
+        "--wallet",
+        "ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3",
+        "--cluster",
+        "devnet",
+    ]);
+    fs::remove_file(&path).unwrap();
+    assert_eq!(code, Some(0));
+    assert_eq!(
+        stdout,
+        "Balance for ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3 on devnet: 100 lamports (0.0000001 SOL)\n"
+    );
+}
+
+/// Tests that a CSV input file is read when `--format csv` is given
+#[test]
+fn test_balance_from_csv_file() {
+    let path = std::env::temp_dir().join("ryz_cli_balance_from_csv_file.csv");
+    fs::write(
+        &path,
+        "type,wallet,amount\n\
+         deposit,ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3,100\n\
+         withdrawal,ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3,40\n",
+    )
+    .unwrap();
+
+    let (code, stdout) = run_with_args(&[
+        "--input",
+        path.to_str().unwrap(),
+        "--format",
+        "csv",
+        "--wallet",
+        "ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3",
+    ]);
+    fs::remove_file(&path).unwrap();
+    assert_eq!(code, Some(0));
+    assert_eq!(
+        stdout,
+        "Balance for ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3 on mainnet: 60 lamports (0.00000006 SOL)\n"
+    );
+}
+
+/// Tests that a missing input file exits with the I/O error code
+#[test]
+fn test_missing_input_file() {
+    let path = std::env::temp_dir().join("ryz_cli_missing_input_file.json");
+    let (code, stdout) = run_with_args(&["--input", path.to_str().unwrap()]);
+    assert_eq!(code, Some(11));
+    assert!(stdout.is_empty());
+}
@@ -0,0 +1,75 @@
+//! Property tests for invariants of the balance calculation.
+
+use proptest::prelude::*;
+use ryz_coding_challenge::{
+    calculate_native_balances, calculate_wallet_balance, is_valid_solana_address, Transaction, TransactionType,
+    WalletAddress,
+};
+
+/// Large enough to exercise big balances, small enough that a full history's sum fits in an `i64`
+const MAX_AMOUNT: i64 = i64::MAX / 64;
+
+/// Generates Solana-shaped addresses: base58-encoded 32-byte keys that pass address validation
+fn solana_address() -> impl Strategy<Value = String> {
+    any::<[u8; 32]>()
+        .prop_map(|key| bs58::encode(key).into_string())
+        .prop_filter("address must pass validation", |address| is_valid_solana_address(address))
+}
+
+/// Generates an amount that `Transaction::new` accepts
+fn amount() -> impl Strategy<Value = i64> {
+    1..=MAX_AMOUNT
+}
+
+proptest! {
+    /// Tests that a deposit-only history's balance equals the sum of its amounts
+    #[test]
+    fn test_deposit_only_balance_is_sum(address in solana_address(), amounts in prop::collection::vec(amount(), 1..32)) {
+        let transactions: Vec<Transaction> = amounts
+            .iter()
+            .map(|&amount| Transaction::new(TransactionType::Deposit, address.clone(), amount).unwrap())
+            .collect();
+
+        let wallet = WalletAddress::parse(&address).unwrap();
+        prop_assert_eq!(calculate_wallet_balance(&wallet, &transactions).unwrap(), amounts.iter().sum::<i64>());
+    }
+
+    /// Tests that reversing each deposit with an equal withdrawal yields zero, withdrawals first or last
+    #[test]
+    fn test_reversed_deposits_balance_to_zero(
+        address in solana_address(),
+        amounts in prop::collection::vec(amount(), 1..32),
+        withdraw_first in any::<bool>(),
+    ) {
+        let deposits = amounts.iter().map(|&amount| Transaction::new(TransactionType::Deposit, address.clone(), amount).unwrap());
+        let withdrawals = amounts.iter().map(|&amount| Transaction::new(TransactionType::Withdrawal, address.clone(), amount).unwrap());
+        let transactions: Vec<Transaction> = if withdraw_first {
+            withdrawals.chain(deposits).collect()
+        } else {
+            deposits.chain(withdrawals).collect()
+        };
+
+        let wallet = WalletAddress::parse(&address).unwrap();
+        prop_assert_eq!(calculate_wallet_balance(&wallet, &transactions).unwrap(), 0);
+    }
+
+    /// Tests that a transfer moves funds between wallets without creating or destroying any
+    #[test]
+    fn test_transfer_conserves_total(
+        from in solana_address(),
+        to in solana_address(),
+        deposit in amount(),
+        transfer in amount(),
+    ) {
+        prop_assume!(from != to);
+        let transactions = vec![
+            Transaction::new(TransactionType::Deposit, from.clone(), deposit).unwrap(),
+            Transaction::new(TransactionType::Transfer { to: to.clone() }, from.clone(), transfer).unwrap(),
+        ];
+
+        let balances = calculate_native_balances(&transactions).unwrap();
+        prop_assert_eq!(balances[&from], deposit - transfer);
+        prop_assert_eq!(balances[&to], transfer);
+        prop_assert_eq!(balances.values().sum::<i64>(), deposit);
+    }
+}
@@ -0,0 +1,47 @@
+//! Integration tests for the binary's stdin mode.
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// Runs the binary with `input` piped to stdin, returning its exit code and stdout
+fn run_with_stdin(input: &str) -> (Option<i32>, String) {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_ryz-coding-challenge"))
+        .env_remove("SOLANA_CLUSTER")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(input.as_bytes()).unwrap();
+    let output = child.wait_with_output().unwrap();
+    (output.status.code(), String::from_utf8(output.stdout).unwrap())
+}
+
+/// Tests that piped JSON lines produce a balance per wallet
+#[test]
+fn test_balances_from_stdin() {
+    let input = concat!(
+        r#"{"transaction_type":"deposit","wallet_address":"ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3","amount":100}"#,
+        "\n",
+        r#"{"transaction_type":"deposit","wallet_address":"9jLkNAaW9E47LQMHvjohy2uAAyr1331bAxgJKFRU7wF6","amount":250}"#,
+        "\n",
+    );
+
+    let (code, stdout) = run_with_stdin(input);
+    assert_eq!(code, Some(0));
+    assert_eq!(
+        stdout,
+        "Cluster: mainnet\n\
+         9jLkNAaW9E47LQMHvjohy2uAAyr1331bAxgJKFRU7wF6: 250 lamports (0.00000025 SOL)\n\
+         ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3: 100 lamports (0.0000001 SOL)\n"
+    );
+}
+
+/// Tests that an invalid piped transaction exits with its error code
+#[test]
+fn test_invalid_stdin_exit_code() {
+    let input = r#"{"transaction_type":"deposit","wallet_address":"ALiCEqZUF4VYuxTu1UQvzDqbpGYYFrxH6kQxWFB8Nqp3","amount":0}"#;
+    let (code, stdout) = run_with_stdin(input);
+    assert_eq!(code, Some(22));
+    assert!(stdout.is_empty());
+}